@@ -0,0 +1,432 @@
+//! analysis of variance, post-hoc comparisons and tests of the homogeneity of variances
+use crate::distribution::{ChiSquared, FisherF, StudentT, StudentizedRange};
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::{FTestResult, TestResult};
+use crate::regression::multiple_regression;
+use crate::uni_dimentional::{mean, median, sample_variance, sort_float, sum};
+
+/// one line of an anova table
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnovaRow {
+    /// name of the source of variation such as `"groups"` or `"residuals"`
+    pub source: &'static str,
+    pub sum_of_squares: Num,
+    pub df: Num,
+    pub mean_square: Num,
+    /// F statistic, `None` for the residual line
+    pub f_statistic: Option<Num>,
+    pub p_value: Option<Num>,
+}
+
+impl AnovaRow {
+    fn new(source: &'static str, sum_of_squares: Num, df: Num) -> Self {
+        AnovaRow {
+            source,
+            sum_of_squares,
+            df,
+            mean_square: sum_of_squares / df,
+            f_statistic: None,
+            p_value: None,
+        }
+    }
+
+    /// tests this source against the error line `residual`
+    fn tested_against(mut self, residual: &AnovaRow) -> Self {
+        let f = self.mean_square / residual.mean_square;
+        self.f_statistic = Some(f);
+        self.p_value = Some(FisherF::new(self.df, residual.df).sf(f));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnovaTable {
+    pub rows: Vec<AnovaRow>,
+    /// sum of the squared deviations of every observation from the grand mean, which the lines only add up to
+    /// when their sums of squares are sequential
+    pub total_sum_of_squares: Num,
+}
+
+impl AnovaTable {
+    /// finds the line of `source`
+    pub fn row(&self, source: &str) -> Option<&AnovaRow> {
+        self.rows.iter().find(|r| r.source == source)
+    }
+
+    /// the error line that the other sources are tested against
+    pub fn residuals(&self) -> &AnovaRow {
+        self.row("residuals").unwrap()
+    }
+}
+
+fn squared_deviations(input: &Input, center: Num) -> Num {
+    input
+        .iter()
+        .fold(0 as Num, |acc, x| acc + (x - center).powi(2))
+}
+
+fn grand_mean(groups: &[&Input]) -> Num {
+    let total = groups.iter().map(|g| sum(g)).sum::<Num>();
+    let count = groups.iter().map(|g| g.len()).sum::<usize>();
+    total / count as Num
+}
+
+/// classic one-way anova. the table has a `"groups"` and a `"residuals"` line
+pub fn one_way_anova(groups: &[&Input]) -> AnovaTable {
+    let grand = grand_mean(groups);
+    let count = groups.iter().map(|g| g.len()).sum::<usize>();
+
+    let between = groups.iter().fold(0 as Num, |acc, g| {
+        acc + g.len() as Num * (mean(g) - grand).powi(2)
+    });
+    let within = groups
+        .iter()
+        .fold(0 as Num, |acc, g| acc + squared_deviations(g, mean(g)));
+
+    let residuals = AnovaRow::new("residuals", within, (count - groups.len()) as Num);
+    let groups =
+        AnovaRow::new("groups", between, (groups.len() - 1) as Num).tested_against(&residuals);
+
+    AnovaTable {
+        rows: vec![groups, residuals],
+        total_sum_of_squares: between + within,
+    }
+}
+
+/// welch's one-way anova, which does not assume equal variances
+pub fn welch_anova(groups: &[&Input]) -> FTestResult {
+    let k = groups.len() as Num;
+    let weights = groups
+        .iter()
+        .map(|g| g.len() as Num / sample_variance(g))
+        .collect::<Vec<Num>>();
+    let total_weight = sum(&weights);
+    let weighted_mean = groups
+        .iter()
+        .zip(weights.iter())
+        .fold(0 as Num, |acc, (g, w)| acc + w * mean(g))
+        / total_weight;
+
+    let a = groups
+        .iter()
+        .zip(weights.iter())
+        .fold(0 as Num, |acc, (g, w)| {
+            acc + w * (mean(g) - weighted_mean).powi(2)
+        })
+        / (k - 1.);
+    let lambda = groups
+        .iter()
+        .zip(weights.iter())
+        .fold(0 as Num, |acc, (g, w)| {
+            acc + (1. - w / total_weight).powi(2) / (g.len() as Num - 1.)
+        });
+    let b = 1. + 2. * (k - 2.) / (k * k - 1.) * lambda;
+
+    let statistic = a / b;
+    let df1 = k - 1.;
+    let df2 = (k * k - 1.) / (3. * lambda);
+    FTestResult {
+        statistic,
+        df1,
+        df2,
+        p_value: FisherF::new(df1, df2).sf(statistic),
+    }
+}
+
+/// residual sum of squares of the least squares fit of `input` on an intercept and the indicator
+/// variables of every level but the first of each factor
+fn additive_residual_sum_of_squares(input: &Input, factors: &[(&[usize], usize)]) -> Num {
+    let indicators = factors
+        .iter()
+        .flat_map(|(factor, levels)| {
+            (1..*levels).map(move |level| {
+                factor
+                    .iter()
+                    .map(|l| if *l == level { 1. } else { 0. })
+                    .collect::<Vec<Num>>()
+            })
+        })
+        .collect::<Vec<Vec<Num>>>();
+    let predictors = indicators.iter().map(|c| &c[..]).collect::<Vec<&Input>>();
+    let fit = multiple_regression(&predictors, input, true);
+    fit.residuals.iter().map(|r| r * r).sum()
+}
+
+/// sum of the squared deviations of every group from its own mean
+fn within_sum_of_squares(groups: &[Vec<Num>]) -> Num {
+    groups
+        .iter()
+        .filter(|g| !g.is_empty())
+        .fold(0 as Num, |acc, g| acc + squared_deviations(g, mean(g)))
+}
+
+/// levels renumbered from 0 in the order of their values, leaving out the ones no observation has,
+/// and how many remain
+fn observed_levels(factor: &[usize]) -> (Vec<usize>, usize) {
+    let mut present = factor.to_vec();
+    present.sort_unstable();
+    present.dedup();
+    let levels = factor
+        .iter()
+        .map(|l| present.binary_search(l).unwrap())
+        .collect();
+    (levels, present.len())
+}
+
+/// two-way anova with interaction.
+/// `factor_a[i]` and `factor_b[i]` are the 0 based levels of `input[i]`. levels without observations are left out.
+/// designs may be unbalanced: the main effects use type II sums of squares, each adjusted for the other factor,
+/// and the interaction is what the full model explains beyond the additive one.
+/// for balanced designs these are the classic sums of squares.
+/// the table has `"a"`, `"b"`, `"a:b"` and `"residuals"` lines
+pub fn two_way_anova(input: &Input, factor_a: &[usize], factor_b: &[usize]) -> AnovaTable {
+    let (factor_a, levels_a) = observed_levels(factor_a);
+    let (factor_b, levels_b) = observed_levels(factor_b);
+
+    let mut cells = vec![Vec::new(); levels_a * levels_b];
+    let mut rows = vec![Vec::new(); levels_a];
+    let mut columns = vec![Vec::new(); levels_b];
+    for ((x, a), b) in input.iter().zip(factor_a.iter()).zip(factor_b.iter()) {
+        cells[a * levels_b + b].push(*x);
+        rows[*a].push(*x);
+        columns[*b].push(*x);
+    }
+    let filled = cells.iter().filter(|c| !c.is_empty()).count() as Num;
+    let (fa, fb) = (levels_a as Num, levels_b as Num);
+
+    let rss_a = within_sum_of_squares(&rows);
+    let rss_b = within_sum_of_squares(&columns);
+    let rss_additive =
+        additive_residual_sum_of_squares(input, &[(&factor_a, levels_a), (&factor_b, levels_b)]);
+    let ss_error = within_sum_of_squares(&cells);
+
+    let residuals = AnovaRow::new("residuals", ss_error, input.len() as Num - filled);
+    let a = AnovaRow::new("a", rss_b - rss_additive, fa - 1.).tested_against(&residuals);
+    let b = AnovaRow::new("b", rss_a - rss_additive, fb - 1.).tested_against(&residuals);
+    let interaction = AnovaRow::new("a:b", rss_additive - ss_error, filled - fa - fb + 1.)
+        .tested_against(&residuals);
+
+    AnovaTable {
+        rows: vec![a, b, interaction, residuals],
+        total_sum_of_squares: squared_deviations(input, mean(input)),
+    }
+}
+
+/// one-way repeated measures anova.
+/// each element of `subjects` holds the measurements of one subject under every condition.
+/// the table has `"conditions"`, `"subjects"` and `"residuals"` lines
+pub fn repeated_measures_anova(subjects: &[&Input]) -> AnovaTable {
+    let n = subjects.len();
+    let k = subjects[0].len();
+    let grand = grand_mean(subjects);
+
+    let ss_total = subjects
+        .iter()
+        .fold(0 as Num, |acc, s| acc + squared_deviations(s, grand));
+    let ss_subjects = subjects.iter().fold(0 as Num, |acc, s| {
+        acc + k as Num * (mean(s) - grand).powi(2)
+    });
+    let ss_conditions = (0..k).fold(0 as Num, |acc, j| {
+        let condition_mean = subjects.iter().map(|s| s[j]).sum::<Num>() / n as Num;
+        acc + n as Num * (condition_mean - grand).powi(2)
+    });
+    let ss_error = ss_total - ss_subjects - ss_conditions;
+
+    let (fn_, fk) = (n as Num, k as Num);
+    let residuals = AnovaRow::new("residuals", ss_error, (fn_ - 1.) * (fk - 1.));
+    let conditions = AnovaRow::new("conditions", ss_conditions, fk - 1.).tested_against(&residuals);
+    let subjects = AnovaRow::new("subjects", ss_subjects, fn_ - 1.);
+
+    AnovaTable {
+        rows: vec![conditions, subjects, residuals],
+        total_sum_of_squares: ss_total,
+    }
+}
+
+/// comparison of two groups made by a post-hoc test
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairwiseComparison {
+    /// index of the first group
+    pub group1: usize,
+    /// index of the second group
+    pub group2: usize,
+    /// `mean(groups[group1]) - mean(groups[group2])`
+    pub mean_difference: Num,
+    pub standard_error: Num,
+    pub statistic: Num,
+    /// p-value adjusted for the number of comparisons
+    pub p_value: Num,
+    /// simultaneous confidence interval of the mean difference
+    pub confidence_interval: (Num, Num),
+}
+
+fn pairs(k: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..k).flat_map(move |i| (i + 1..k).map(move |j| (i, j)))
+}
+
+/// tukey's honestly significant difference (tukey–kramer for unequal group sizes).
+/// the statistic is the studentized range `|difference| / standard_error`
+pub fn tukey_hsd(groups: &[&Input], level: Num) -> Vec<PairwiseComparison> {
+    let table = one_way_anova(groups);
+    let residuals = table.residuals();
+    let distribution = StudentizedRange::new(groups.len() as Num, residuals.df);
+    let critical = distribution.quantile(level);
+
+    pairs(groups.len())
+        .map(|(i, j)| {
+            let difference = mean(groups[i]) - mean(groups[j]);
+            let standard_error = (residuals.mean_square / 2.
+                * (1. / groups[i].len() as Num + 1. / groups[j].len() as Num))
+                .sqrt();
+            let statistic = difference.abs() / standard_error;
+            PairwiseComparison {
+                group1: i,
+                group2: j,
+                mean_difference: difference,
+                standard_error,
+                statistic,
+                p_value: distribution.sf(statistic),
+                confidence_interval: (
+                    difference - critical * standard_error,
+                    difference + critical * standard_error,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// pairwise t-tests on the pooled standard deviation with bonferroni adjusted p-values and intervals
+pub fn bonferroni_pairwise(groups: &[&Input], level: Num) -> Vec<PairwiseComparison> {
+    let table = one_way_anova(groups);
+    let residuals = table.residuals();
+    let distribution = StudentT::new(residuals.df);
+    let k = groups.len() as Num;
+    let comparisons = k * (k - 1.) / 2.;
+    let critical = distribution.quantile(1. - (1. - level) / (2. * comparisons));
+
+    pairs(groups.len())
+        .map(|(i, j)| {
+            let difference = mean(groups[i]) - mean(groups[j]);
+            let standard_error = (residuals.mean_square
+                * (1. / groups[i].len() as Num + 1. / groups[j].len() as Num))
+                .sqrt();
+            let statistic = difference / standard_error;
+            PairwiseComparison {
+                group1: i,
+                group2: j,
+                mean_difference: difference,
+                standard_error,
+                statistic,
+                p_value: (2. * distribution.cdf(-statistic.abs()) * comparisons).min(1.),
+                confidence_interval: (
+                    difference - critical * standard_error,
+                    difference + critical * standard_error,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// games–howell test. like tukey's HSD but with welch's standard errors and degrees of freedom,
+/// so the groups may have different variances
+pub fn games_howell(groups: &[&Input], level: Num) -> Vec<PairwiseComparison> {
+    let k = groups.len() as Num;
+
+    pairs(groups.len())
+        .map(|(i, j)| {
+            let (a, b) = (groups[i], groups[j]);
+            let (na, nb) = (a.len() as Num, b.len() as Num);
+            let va = sample_variance(a) / na;
+            let vb = sample_variance(b) / nb;
+            let df = (va + vb).powi(2) / (va * va / (na - 1.) + vb * vb / (nb - 1.));
+            let distribution = StudentizedRange::new(k, df);
+
+            let difference = mean(a) - mean(b);
+            let standard_error = ((va + vb) / 2.).sqrt();
+            let statistic = difference.abs() / standard_error;
+            let critical = distribution.quantile(level);
+            PairwiseComparison {
+                group1: i,
+                group2: j,
+                mean_difference: difference,
+                standard_error,
+                statistic,
+                p_value: distribution.sf(statistic),
+                confidence_interval: (
+                    difference - critical * standard_error,
+                    difference + critical * standard_error,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// one-way anova F test on the absolute deviations of each group from `center(group)`
+fn deviation_anova<F>(groups: &[&Input], center: F) -> FTestResult
+where
+    F: Fn(&Input) -> Num,
+{
+    let deviations = groups
+        .iter()
+        .map(|g| {
+            let c = center(g);
+            g.iter().map(|x| (x - c).abs()).collect::<Vec<Num>>()
+        })
+        .collect::<Vec<Vec<Num>>>();
+    let deviations = deviations.iter().map(|d| &d[..]).collect::<Vec<&Input>>();
+
+    let table = one_way_anova(&deviations);
+    let line = table.row("groups").unwrap();
+    FTestResult {
+        statistic: line.f_statistic.unwrap(),
+        df1: line.df,
+        df2: table.residuals().df,
+        p_value: line.p_value.unwrap(),
+    }
+}
+
+/// levene's test of equal variances, measuring deviations from the group means
+pub fn levene(groups: &[&Input]) -> FTestResult {
+    deviation_anova(groups, mean)
+}
+
+/// brown–forsythe test of equal variances. it is levene's test on deviations from the group medians,
+/// which makes it robust against non normal data
+pub fn brown_forsythe(groups: &[&Input]) -> FTestResult {
+    deviation_anova(groups, |g| median(&sort_float(g)))
+}
+
+/// bartlett's test of equal variances for normally distributed groups
+pub fn bartlett(groups: &[&Input]) -> TestResult {
+    let k = groups.len() as Num;
+    let count = groups.iter().map(|g| g.len()).sum::<usize>() as Num;
+    let dfs = groups
+        .iter()
+        .map(|g| g.len() as Num - 1.)
+        .collect::<Vec<Num>>();
+    let variances = groups
+        .iter()
+        .map(|g| sample_variance(g))
+        .collect::<Vec<Num>>();
+
+    let pooled = dfs
+        .iter()
+        .zip(variances.iter())
+        .fold(0 as Num, |acc, (d, v)| acc + d * v)
+        / (count - k);
+    let numerator = (count - k) * pooled.ln()
+        - dfs
+            .iter()
+            .zip(variances.iter())
+            .fold(0 as Num, |acc, (d, v)| acc + d * v.ln());
+    let correction =
+        1. + (dfs.iter().map(|d| 1. / d).sum::<Num>() - 1. / (count - k)) / (3. * (k - 1.));
+
+    let statistic = numerator / correction;
+    let df = k - 1.;
+    TestResult {
+        statistic,
+        df: Some(df),
+        p_value: ChiSquared::new(df).sf(statistic),
+    }
+}
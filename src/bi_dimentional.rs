@@ -1,5 +1,11 @@
 /// functions that takes two argument will be found hereuuse crate::generic_types::*;
+use crate::distribution::{Normal, StudentT};
 use crate::generic_types::*;
+use crate::hypothesis::Alternative;
+use crate::regression::{
+    design_matrix, nonlinear_least_squares, r_squared, residuals, robust_coefficients, summarize,
+    Coefficient, RegressionSummary, RobustCovariance,
+};
 use crate::uni_dimentional::*;
 use std::f64::consts::E as NumE;
 
@@ -42,28 +48,312 @@ pub fn auto_correlation(input: &Input, lags: Int) -> Num {
 
     result
 }
-#[derive(Debug, Default)]
-pub struct LinearRegression<'a> {
+
+/// result of a test of no association between two samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationTestResult {
+    pub coefficient: Num,
+    pub statistic: Num,
+    pub p_value: Num,
+    /// confidence interval of the coefficient from fisher's z transformation
+    pub confidence_interval: (Num, Num),
+}
+
+/// variants of kendall's tau, which differ in how they treat ties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KendallVariant {
+    /// `(concordant - discordant) / (n (n - 1) / 2)`, ignores ties
+    A,
+    /// adjusted for ties in both samples, reaches ±1 on square tables
+    B,
+    /// stuart's tau-c, adjusted for rectangular tables
+    C,
+}
+
+/// fisher z interval of a correlation with the given standard error of `atanh(coefficient)`
+fn fisher_interval(
+    coefficient: Num,
+    standard_error: Num,
+    alternative: Alternative,
+    level: Num,
+) -> (Num, Num) {
+    let normal = Normal::standard();
+    let (lower, upper) =
+        alternative.symmetric_interval(coefficient.atanh(), standard_error, level, |q| {
+            normal.quantile(q)
+        });
+    (lower.tanh(), upper.tanh())
+}
+
+/// spearman's rank correlation, the pearson correlation of the ranks. ties get their average rank
+pub fn spearman_rho(input1: &Input, input2: &Input) -> Num {
+    correlation(
+        &rank(input1, TieMethod::Average),
+        &rank(input2, TieMethod::Average),
+    )
+}
+
+/// spearman's rho with the t approximation `rho sqrt((n - 2) / (1 - rho^2))` of its null distribution.
+/// the confidence interval uses the fieller, hartley and pearson standard error `sqrt(1.06 / (n - 3))`
+pub fn spearman_test(
+    input1: &Input,
+    input2: &Input,
+    alternative: Alternative,
+    level: Num,
+) -> CorrelationTestResult {
+    let n = input1.len() as Num;
+    let coefficient = spearman_rho(input1, input2);
+    let statistic = coefficient * ((n - 2.) / (1. - coefficient * coefficient)).sqrt();
+    let distribution = StudentT::new(n - 2.);
+
+    CorrelationTestResult {
+        coefficient,
+        statistic,
+        p_value: alternative.symmetric_p_value(statistic, |x| distribution.cdf(x)),
+        confidence_interval: fisher_interval(
+            coefficient,
+            (1.06 / (n - 3.)).sqrt(),
+            alternative,
+            level,
+        ),
+    }
+}
+
+/// pair counts of kendall's tau
+struct KendallCounts {
+    /// concordant minus discordant pairs
+    score: Num,
+    /// sizes of the groups of tied values in each sample
+    ties1: Vec<usize>,
+    ties2: Vec<usize>,
+}
+
+/// sizes of the runs of equal values in a sorted slice
+fn tie_groups<T: PartialEq>(sorted: &[T]) -> Vec<usize> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || sorted[i] != sorted[start] {
+            groups.push(i - start);
+            start = i;
+        }
+    }
+    groups
+}
+
+/// merge sorts `values` and returns how many pairs were strictly out of order
+fn count_swaps(values: &mut [Num]) -> usize {
+    let n = values.len();
+    if n < 2 {
+        return 0;
+    }
+    let middle = n / 2;
+    let mut swaps = count_swaps(&mut values[..middle]) + count_swaps(&mut values[middle..]);
+
+    let mut merged = Vec::with_capacity(n);
+    let (mut i, mut j) = (0, middle);
+    while i < middle && j < n {
+        if values[j] < values[i] {
+            // values[j] comes before every value left in the first half
+            swaps += middle - i;
+            merged.push(values[j]);
+            j += 1;
+        } else {
+            merged.push(values[i]);
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&values[i..middle]);
+    merged.extend_from_slice(&values[j..]);
+    values.copy_from_slice(&merged);
+    swaps
+}
+
+/// counts the pairs with knight's O(n log n) algorithm: sort by the first sample,
+/// then count the swaps a merge sort of the second sample needs
+fn kendall_counts(input1: &Input, input2: &Input) -> KendallCounts {
+    let n = input1.len();
+    let mut order = (0..n).collect::<Vec<usize>>();
+    order.sort_by_key(|i| (OrdFloat(input1[*i]), OrdFloat(input2[*i])));
+
+    let x = order
+        .iter()
+        .map(|i| OrdFloat(input1[*i]))
+        .collect::<Vec<OrdFloat>>();
+    let pairs = order
+        .iter()
+        .map(|i| (OrdFloat(input1[*i]), OrdFloat(input2[*i])))
+        .collect::<Vec<(OrdFloat, OrdFloat)>>();
+    let ties1 = tie_groups(&x);
+    let joint = tie_groups(&pairs);
+
+    let mut y = order.iter().map(|i| input2[*i]).collect::<Vec<Num>>();
+    let swaps = count_swaps(&mut y);
+    let ties2 = tie_groups(&y);
+
+    let tied_pairs =
+        |groups: &[usize]| groups.iter().map(|t| t * (t - 1) / 2).sum::<usize>() as Num;
+    let total = (n * (n - 1) / 2) as Num;
+    let score =
+        total - tied_pairs(&ties1) - tied_pairs(&ties2) + tied_pairs(&joint) - 2. * swaps as Num;
+
+    KendallCounts {
+        score,
+        ties1,
+        ties2,
+    }
+}
+
+impl KendallCounts {
+    fn coefficient(&self, n: usize, variant: KendallVariant) -> Num {
+        let n = n as Num;
+        let total = n * (n - 1.) / 2.;
+        let tied_pairs =
+            |groups: &[usize]| groups.iter().map(|t| t * (t - 1) / 2).sum::<usize>() as Num;
+        match variant {
+            KendallVariant::A => self.score / total,
+            KendallVariant::B => {
+                self.score
+                    / ((total - tied_pairs(&self.ties1)) * (total - tied_pairs(&self.ties2))).sqrt()
+            }
+            KendallVariant::C => {
+                let m = self.ties1.len().min(self.ties2.len()) as Num;
+                2. * m * self.score / (n * n * (m - 1.))
+            }
+        }
+    }
+
+    /// variance of the score under independence, corrected for ties
+    fn variance(&self, n: usize) -> Num {
+        let n = n as Num;
+        let sum = |groups: &[usize], f: &dyn Fn(Num) -> Num| {
+            groups.iter().map(|t| f(*t as Num)).sum::<Num>()
+        };
+        let v0 = n * (n - 1.) * (2. * n + 5.);
+        let vt = sum(&self.ties1, &|t| t * (t - 1.) * (2. * t + 5.));
+        let vu = sum(&self.ties2, &|t| t * (t - 1.) * (2. * t + 5.));
+        let v1 = sum(&self.ties1, &|t| t * (t - 1.)) * sum(&self.ties2, &|t| t * (t - 1.))
+            / (2. * n * (n - 1.));
+        let v2 = sum(&self.ties1, &|t| t * (t - 1.) * (t - 2.))
+            * sum(&self.ties2, &|t| t * (t - 1.) * (t - 2.))
+            / (9. * n * (n - 1.) * (n - 2.));
+        (v0 - vt - vu) / 18. + v1 + v2
+    }
+}
+
+/// kendall's rank correlation tau
+pub fn kendall_tau(input1: &Input, input2: &Input, variant: KendallVariant) -> Num {
+    kendall_counts(input1, input2).coefficient(input1.len(), variant)
+}
+
+/// kendall's tau with the normal approximation of its score, whose variance is corrected for ties.
+/// the statistic is the z score and does not depend on the variant.
+/// the confidence interval uses the fieller, hartley and pearson standard error `sqrt(0.437 / (n - 4))`
+pub fn kendall_test(
+    input1: &Input,
+    input2: &Input,
+    variant: KendallVariant,
+    alternative: Alternative,
+    level: Num,
+) -> CorrelationTestResult {
+    let n = input1.len();
+    let counts = kendall_counts(input1, input2);
+    let coefficient = counts.coefficient(n, variant);
+    let statistic = counts.score / counts.variance(n).sqrt();
+    let normal = Normal::standard();
+
+    CorrelationTestResult {
+        coefficient,
+        statistic,
+        p_value: alternative.symmetric_p_value(statistic, |x| normal.cdf(x)),
+        confidence_interval: fisher_interval(
+            coefficient,
+            (0.437 / (n as Num - 4.)).sqrt(),
+            alternative,
+            level,
+        ),
+    }
+}
+
+/// straight line `intercept + gradient * x` fitted to data.
+/// the model owns its fitted values and residuals, so it can be stored and applied to new data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinearRegression {
     pub gradient: Num,
     pub intercept: Num,
-    pub input_x: Option<&'a Input>,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+    /// weights of the observations of a weighted least squares fit
+    pub weights: Option<Vec<Num>>,
 }
 
-impl<'a> LinearRegression<'a> {
-    pub fn build_y(&self) -> Vec<Num> {
-        self.input_x
-            .unwrap()
+impl LinearRegression {
+    /// model with the given coefficients, with its fitted values and residuals on `input_x` and `input_y`
+    pub(crate) fn new(intercept: Num, gradient: Num, input_x: &Input, input_y: &Input) -> Self {
+        let fitted_values = input_x
             .iter()
-            .map(|i| (*i * self.gradient) + self.intercept)
-            .collect::<Vec<Num>>()
+            .map(|x| intercept + gradient * x)
+            .collect::<Vec<Num>>();
+        LinearRegression {
+            gradient,
+            intercept,
+            residuals: residuals(input_y, &fitted_values),
+            fitted_values,
+            weights: None,
+        }
     }
-    pub fn set_input_x(&mut self, input_x: &'a Input) {
-        self.input_x.replace(input_x);
+
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.intercept + self.gradient * x
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
+    }
+
+    /// least squares diagnostics of the model on the data it was fitted to, with intervals at `level`.
+    /// the standard errors assume the coefficients were fitted by least squares
+    pub fn summary(&self, input_x: &Input, level: Num) -> RegressionSummary {
+        summarize(
+            &design_matrix(&[input_x], true),
+            &[self.intercept, self.gradient],
+            &self.fitted_values,
+            &self.residuals,
+            self.weights.as_deref(),
+            true,
+            level,
+        )
+    }
+
+    /// coefficient tests with standard errors from a heteroscedasticity or autocorrelation
+    /// consistent covariance estimator
+    pub fn robust_coefficients(
+        &self,
+        input_x: &Input,
+        estimator: RobustCovariance,
+        level: Num,
+    ) -> Vec<Coefficient> {
+        robust_coefficients(
+            &design_matrix(&[input_x], true),
+            &[self.intercept, self.gradient],
+            &self.residuals,
+            self.weights.as_deref(),
+            estimator,
+            level,
+        )
     }
 }
 
 /// calculates intercept and gradient of 2 inputs
-pub fn linear_regression<'a>(input_x: &'a Input, input_y: &Input) -> LinearRegression<'a> {
+pub fn linear_regression(input_x: &Input, input_y: &Input) -> LinearRegression {
     let mut sum = [0 as Num; 5];
     for i in 0..input_x.len() {
         let x = input_x[i];
@@ -80,38 +370,71 @@ pub fn linear_regression<'a>(input_x: &'a Input, input_y: &Input) -> LinearRegre
     let gradient = (float_len * sum[3] - sum[0] * sum[1]) / (float_len * sum[2] - sum[0] * sum[0]);
     let intercept = (sum[1] / float_len) - (gradient * sum[0] / float_len);
 
+    LinearRegression::new(intercept, gradient, input_x, input_y)
+}
+
+/// weighted least squares line, which minimizes `sum(weights * residuals^2)`.
+/// weights are usually the inverse variances of the observations
+pub fn weighted_linear_regression(
+    input_x: &Input,
+    input_y: &Input,
+    weights: &Input,
+) -> LinearRegression {
+    let total = weights.iter().sum::<Num>();
+    let weighted_mean = |input: &Input| {
+        input
+            .iter()
+            .zip(weights.iter())
+            .map(|(v, w)| v * w)
+            .sum::<Num>()
+            / total
+    };
+    let (x_mean, y_mean) = (weighted_mean(input_x), weighted_mean(input_y));
+    let (sxy, sxx) = (0..input_x.len()).fold((0 as Num, 0 as Num), |acc, i| {
+        let dx = input_x[i] - x_mean;
+        (
+            acc.0 + weights[i] * dx * (input_y[i] - y_mean),
+            acc.1 + weights[i] * dx * dx,
+        )
+    });
+    let gradient = sxy / sxx;
+
     LinearRegression {
-        gradient,
-        intercept,
-        input_x: Some(input_x),
+        weights: Some(weights.to_vec()),
+        ..LinearRegression::new(y_mean - gradient * x_mean, gradient, input_x, input_y)
     }
 }
 
-pub struct ExponentialRegression<'a> {
+/// exponential curve `a * e^(b x)` fitted to data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExponentialRegression {
     pub a: Num,
     pub b: Num,
-    pub input_x: Option<&'a Input>,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
 }
 
-impl<'a> ExponentialRegression<'a> {
-    pub fn build_y(&self) -> Vec<Num> {
-        self.bulid_y_with_logarithm(NumE)
+impl ExponentialRegression {
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.a * (self.b * x).exp()
     }
-    pub fn bulid_y_with_logarithm(&self, logarithm: Num) -> Vec<Num> {
-        let a = self.a;
-        let b = self.b;
-        self.input_x
-            .unwrap_or(&[0.])
-            .iter()
-            .map(|i| a * logarithm.log(b * i))
-            .collect::<Vec<Num>>()
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
     }
 }
 
-pub fn exponential_regression<'a>(
-    input_x: &'a Input,
-    input_y: &Input,
-) -> ExponentialRegression<'a> {
+/// fits `a * e^(b x)` by least squares on `ln y` weighted by `y`, which offsets the
+/// emphasis the logarithm puts on small values. `input_y` must be positive
+pub fn exponential_regression(input_x: &Input, input_y: &Input) -> ExponentialRegression {
     let mut sum = [0 as Num; 5];
     for i in 0..input_x.len() {
         let x = input_x[i];
@@ -129,16 +452,47 @@ pub fn exponential_regression<'a>(
     let sum_4_pow2 = sum[4] * sum[4];
     let denominator = sum[0] * sum[1] - sum_4_pow2;
     let a = NumE.powf((sum[1] * sum[2] - sum[4] * sum[3]) / denominator);
-    let b = ((sum[0] * sum[3]) - sum_4_pow2) / denominator;
+    let b = (sum[0] * sum[3] - sum[4] * sum[2]) / denominator;
 
-    ExponentialRegression {
+    let mut model = ExponentialRegression {
         a,
         b,
-        input_x: Some(input_x),
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
+/// logarithmic curve `a + b ln(x)` fitted to data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogarithmicRegression {
+    pub a: Num,
+    pub b: Num,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+}
+
+impl LogarithmicRegression {
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.a + self.b * x.ln()
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
     }
 }
 
-pub fn logarithm_regression<'a>(input_x: &'a Input, input_y: &Input) -> ExponentialRegression<'a> {
+/// fits `a + b ln(x)` by least squares. `input_x` must be positive
+pub fn logarithm_regression(input_x: &Input, input_y: &Input) -> LogarithmicRegression {
     let mut sum = [0 as Num; 4];
     for i in 0..input_x.len() {
         let x = input_x[i];
@@ -153,16 +507,165 @@ pub fn logarithm_regression<'a>(input_x: &'a Input, input_y: &Input) -> Exponent
     }
 
     let float_len = input_x.len() as Num;
-    let a = (float_len * sum[1] - sum[2] * sum[0]) / (float_len * sum[3] - sum[0] * sum[0]);
-    let b = (sum[2] - a * sum[0]) / float_len;
+    let b = (float_len * sum[1] - sum[2] * sum[0]) / (float_len * sum[3] - sum[0] * sum[0]);
+    let a = (sum[2] - b * sum[0]) / float_len;
 
-    ExponentialRegression {
+    let mut model = LogarithmicRegression {
         a,
         b,
-        input_x: Some(input_x),
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
+/// power curve `a * x^b` fitted to data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerRegression {
+    pub a: Num,
+    pub b: Num,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+}
+
+impl PowerRegression {
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.a * x.powf(self.b)
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
+    }
+}
+
+/// fits `a * x^b` by least squares on `ln y = ln a + b ln x`. both inputs must be positive
+pub fn power_regression(input_x: &Input, input_y: &Input) -> PowerRegression {
+    let log_x = input_x.iter().map(|x| x.ln()).collect::<Vec<Num>>();
+    let log_y = input_y.iter().map(|y| y.ln()).collect::<Vec<Num>>();
+    let line = linear_regression(&log_x, &log_y);
+
+    let mut model = PowerRegression {
+        a: line.intercept.exp(),
+        b: line.gradient,
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
+/// saturation growth curve `a x / (b + x)`, which rises towards `a` and reaches half of it at `x = b`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaturatingRegression {
+    pub a: Num,
+    pub b: Num,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+}
+
+impl SaturatingRegression {
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.a * x / (self.b + x)
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
     }
 }
 
+/// fits `a x / (b + x)` by nonlinear least squares, starting from the
+/// linear fit of `1 / y = 1 / a + (b / a) / x`. the inputs must be non zero
+pub fn saturating_regression(input_x: &Input, input_y: &Input) -> SaturatingRegression {
+    let reciprocal_x = input_x.iter().map(|x| 1. / x).collect::<Vec<Num>>();
+    let reciprocal_y = input_y.iter().map(|y| 1. / y).collect::<Vec<Num>>();
+    let line = linear_regression(&reciprocal_x, &reciprocal_y);
+    let initial = vec![1. / line.intercept, line.gradient / line.intercept];
+
+    let fitted = nonlinear_least_squares(input_x, input_y, initial, |x, p| p[0] * x / (p[1] + x));
+    let mut model = SaturatingRegression {
+        a: fitted[0],
+        b: fitted[1],
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
+/// logistic growth curve `capacity / (1 + e^(-rate (x - midpoint)))`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogisticGrowthRegression {
+    /// the value the curve levels off at
+    pub capacity: Num,
+    pub rate: Num,
+    /// where the curve reaches half of its capacity and grows the fastest
+    pub midpoint: Num,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+}
+
+impl LogisticGrowthRegression {
+    #[inline]
+    pub fn predict(&self, x: Num) -> Num {
+        self.capacity / (1. + (-self.rate * (x - self.midpoint)).exp())
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// share of the variance of the training data the model explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
+    }
+}
+
+/// fits a logistic growth curve by nonlinear least squares. the starting values take a capacity
+/// a little above the largest observation and fit `ln(capacity / y - 1) = rate (midpoint - x)`.
+/// `input_y` must be positive
+pub fn logistic_growth_regression(input_x: &Input, input_y: &Input) -> LogisticGrowthRegression {
+    let capacity = input_y.iter().fold(Num::NEG_INFINITY, |acc, y| acc.max(*y)) * 1.05;
+    let logit = input_y
+        .iter()
+        .map(|y| (capacity / y - 1.).ln())
+        .collect::<Vec<Num>>();
+    let line = linear_regression(input_x, &logit);
+    let rate = -line.gradient;
+    let initial = vec![capacity, rate, line.intercept / rate];
+
+    let fitted = nonlinear_least_squares(input_x, input_y, initial, |x, p| {
+        p[0] / (1. + (-p[1] * (x - p[2])).exp())
+    });
+    let mut model = LogisticGrowthRegression {
+        capacity: fitted[0],
+        rate: fitted[1],
+        midpoint: fitted[2],
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
 fn distance_base<F>(input1: &Input, input2: &Input, func: F) -> Num
 where
     F: Fn(Num, Num) -> Num,
@@ -181,15 +684,13 @@ pub fn euclidean_distance(input1: &Input, input2: &Input) -> Num {
 }
 
 pub fn chebyshev_distance(input1: &Input, input2: &Input) -> Num {
-    let dis = distance_base(input1, input2, |dif, distance| {
+    distance_base(input1, input2, |dif, distance| {
         if let Some(std::cmp::Ordering::Less) = distance.partial_cmp(&dif.abs()) {
             dif
         } else {
             distance
         }
-    });
-
-    dis
+    })
 }
 
 pub fn manhattan_distance(input1: &Input, input2: &Input) -> Num {
@@ -207,8 +708,8 @@ pub fn covariance_population(input1: &Input, input2: &Input) -> Num {
     let m2 = mean(input2);
 
     let sum = (0..input1.len()).fold(0 as Num, |acc, i| {
-        let d1 = &input1[i] - m1;
-        let d2 = &input2[i] - m2;
+        let d1 = input1[i] - m1;
+        let d2 = input2[i] - m2;
         acc + d1 * d2
     });
 
@@ -221,12 +722,12 @@ pub fn sample_covariance(input1: &Input, input2: &Input) -> Num {
 
     let mut s = 0 as Num;
     for i in 0..input1.len() {
-        let d1 = &input1[i] - m1;
-        let d2 = &input2[i] - m2;
+        let d1 = input1[i] - m1;
+        let d2 = input2[i] - m2;
         s += d1 * d2;
     }
 
     let l = input1.len();
 
-    s * (l as Num) / ((l - 1) as Num)
+    s / ((l - 1) as Num)
 }
@@ -0,0 +1,520 @@
+//! contingency tables and tests of association between categorical variables
+use std::collections::{BTreeMap, HashMap};
+
+use crate::distribution::special::ln_gamma;
+use crate::distribution::{ChiSquared, Hypergeometric};
+use crate::generic_types::{Num, OrdFloat};
+use crate::hypothesis::{Alternative, TestResult};
+
+/// probabilities closer than this ratio are treated as equal by the exact tests
+const RELATIVE_ERROR: Num = 1e-7;
+
+/// cross tabulation of two categorical variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContingencyTable {
+    observed: Vec<Vec<Num>>,
+}
+
+impl ContingencyTable {
+    /// counts how many times each pair `(rows[i], columns[i])` is observed.
+    /// rows and columns are ordered by their labels
+    pub fn new<R: Ord, C: Ord>(rows: &[R], columns: &[C]) -> Self {
+        let row_index = label_index(rows);
+        let column_index = label_index(columns);
+
+        let mut observed = vec![vec![0 as Num; column_index.len()]; row_index.len()];
+        for (r, c) in rows.iter().zip(columns.iter()) {
+            observed[row_index[r]][column_index[c]] += 1.;
+        }
+        ContingencyTable { observed }
+    }
+
+    /// builds a table from counts that are already tabulated. `observed[i][j]` is row `i` and column `j`
+    pub fn from_counts(observed: Vec<Vec<Num>>) -> Self {
+        ContingencyTable { observed }
+    }
+
+    #[inline]
+    pub fn observed(&self) -> &Vec<Vec<Num>> {
+        &self.observed
+    }
+
+    #[inline]
+    pub fn n_rows(&self) -> usize {
+        self.observed.len()
+    }
+
+    #[inline]
+    pub fn n_columns(&self) -> usize {
+        self.observed.first().map_or(0, |r| r.len())
+    }
+
+    pub fn row_totals(&self) -> Vec<Num> {
+        self.observed.iter().map(|r| r.iter().sum()).collect()
+    }
+
+    pub fn column_totals(&self) -> Vec<Num> {
+        (0..self.n_columns())
+            .map(|j| self.observed.iter().map(|r| r[j]).sum())
+            .collect()
+    }
+
+    pub fn total(&self) -> Num {
+        self.row_totals().iter().sum()
+    }
+
+    /// counts expected under independence, `row total * column total / total`
+    pub fn expected(&self) -> Vec<Vec<Num>> {
+        let rows = self.row_totals();
+        let columns = self.column_totals();
+        let total = self.total();
+        rows.iter()
+            .map(|r| columns.iter().map(|c| r * c / total).collect())
+            .collect()
+    }
+
+    fn df(&self) -> Num {
+        ((self.n_rows() - 1) * (self.n_columns() - 1)) as Num
+    }
+
+    fn is_2x2(&self) -> bool {
+        self.n_rows() == 2 && self.n_columns() == 2
+    }
+
+    /// pearson's chi-square test of independence.
+    /// `yates` applies yates' continuity correction, which only affects 2×2 tables
+    pub fn chi_square(&self, yates: bool) -> TestResult {
+        let expected = self.expected();
+        let correction = if yates && self.is_2x2() { 0.5 } else { 0. };
+
+        let mut statistic = 0 as Num;
+        for (observed, expected) in self.observed.iter().zip(expected.iter()) {
+            for (o, e) in observed.iter().zip(expected.iter()) {
+                let difference = ((o - e).abs() - correction).max(0.);
+                statistic += difference * difference / e;
+            }
+        }
+
+        let df = self.df();
+        TestResult {
+            statistic,
+            df: Some(df),
+            p_value: ChiSquared::new(df).sf(statistic),
+        }
+    }
+
+    /// likelihood ratio (G) test of independence
+    pub fn g_test(&self) -> TestResult {
+        let expected = self.expected();
+
+        let mut statistic = 0 as Num;
+        for (observed, expected) in self.observed.iter().zip(expected.iter()) {
+            for (o, e) in observed.iter().zip(expected.iter()) {
+                if *o > 0. {
+                    statistic += 2. * o * (o / e).ln();
+                }
+            }
+        }
+
+        let df = self.df();
+        TestResult {
+            statistic,
+            df: Some(df),
+            p_value: ChiSquared::new(df).sf(statistic),
+        }
+    }
+
+    /// fisher's exact test of a 2×2 table.
+    /// the statistic is the sample odds ratio and `Less`/`Greater` refer to the odds ratio being below/above 1
+    pub fn fisher_exact_2x2(&self, alternative: Alternative) -> TestResult {
+        assert!(self.is_2x2(), "fisher_exact_2x2 needs a 2x2 table");
+        let o = &self.observed;
+        let a = o[0][0] as u64;
+        let rows = self.row_totals();
+        let columns = self.column_totals();
+        let distribution =
+            Hypergeometric::new(self.total() as u64, rows[0] as u64, columns[0] as u64);
+
+        let p_value = match alternative {
+            Alternative::Less => distribution.cdf(a),
+            Alternative::Greater => {
+                if a == 0 {
+                    1.
+                } else {
+                    distribution.sf(a - 1)
+                }
+            }
+            Alternative::TwoSided => {
+                let threshold = distribution.pmf(a) * (1. + RELATIVE_ERROR);
+                (distribution.min()..=distribution.max())
+                    .map(|k| distribution.pmf(k))
+                    .filter(|p| *p <= threshold)
+                    .sum::<Num>()
+                    .min(1.)
+            }
+        };
+
+        TestResult {
+            statistic: o[0][0] * o[1][1] / (o[0][1] * o[1][0]),
+            df: None,
+            p_value,
+        }
+    }
+
+    /// two sided fisher's exact test of a table of any size, by the network algorithm of mehta and patel.
+    /// columns are stages and nodes are the remaining row totals, and paths whose past probabilities
+    /// are equal are merged. bounds on the shortest and longest paths from a node to the end of the
+    /// network drop the paths whose completions are all more probable than the observed table,
+    /// and sum in closed form those whose completions are all as probable or less.
+    /// the statistic is the probability of the observed table
+    pub fn fisher_exact(&self) -> TestResult {
+        let rows = self
+            .row_totals()
+            .iter()
+            .map(|r| *r as u64)
+            .collect::<Vec<u64>>();
+        // small columns first keep the early stages, where paths are not yet decided, narrow
+        let mut columns = self
+            .column_totals()
+            .iter()
+            .map(|c| *c as u64)
+            .collect::<Vec<u64>>();
+        columns.sort_unstable();
+
+        let ln_factorials = (0..=rows.iter().sum::<u64>())
+            .map(|n| ln_factorial(n as Num))
+            .collect::<Vec<Num>>();
+
+        // P(table) = exp(constant - sum(ln x_ij!))
+        let constant = -completions(&rows, &columns, &ln_factorials);
+        let observed_past = self
+            .observed
+            .iter()
+            .flatten()
+            .map(|x| -ln_factorial(*x))
+            .sum::<Num>();
+        let threshold = constant + observed_past + (1. + RELATIVE_ERROR).ln();
+
+        // past values are keyed by their rounded logarithm so that equal ones are merged
+        let key = |past: Num| (past / RELATIVE_ERROR).round() as i64;
+        let mut start = rows;
+        start.sort_unstable();
+
+        let mut p_value = 0 as Num;
+        let mut stage = HashMap::new();
+        stage.insert(start, Pasts::new(vec![(0., 1.)], constant));
+
+        for (k, column) in columns.iter().enumerate() {
+            let later = &columns[k + 1..];
+            let mut bounds: HashMap<Vec<u64>, (Num, Num)> = HashMap::new();
+            let mut next: HashMap<Vec<u64>, HashMap<i64, (Num, Num)>> = HashMap::new();
+            for (remaining, pasts) in stage.iter() {
+                for_each_column(remaining, *column, &mut |entries: &[u64]| {
+                    let step = -entries
+                        .iter()
+                        .map(|x| ln_factorials[*x as usize])
+                        .sum::<Num>();
+                    if later.len() <= 1 {
+                        // the rest of the table is fixed, so the paths through this edge are complete
+                        let future = -remaining
+                            .iter()
+                            .zip(entries.iter())
+                            .map(|(r, x)| ln_factorials[(r - x) as usize])
+                            .sum::<Num>();
+                        let counted = pasts.below(threshold - constant - step - future);
+                        p_value += pasts.probability(counted) * (step + future).exp();
+                        return;
+                    }
+
+                    let mut node = remaining
+                        .iter()
+                        .zip(entries.iter())
+                        .map(|(r, x)| r - x)
+                        .collect::<Vec<u64>>();
+                    node.sort_unstable();
+                    let (shortest, longest) = *bounds
+                        .entry(node.clone())
+                        .or_insert_with(|| path_bounds(&node, later, &ln_factorials));
+
+                    // the pasts are sorted, those whose longest completion is still unlikely enough
+                    // all count and those whose shortest one is too likely never do
+                    let counted = pasts.below(threshold - constant - step - longest);
+                    let open = pasts.below(threshold - constant - step - shortest);
+                    if counted > 0 {
+                        let all = completions(&node, later, &ln_factorials);
+                        p_value += pasts.probability(counted) * (step + all).exp();
+                    }
+                    if open > counted {
+                        let target = next.entry(node).or_default();
+                        for (past, multiplicity) in pasts.paths[counted..open].iter() {
+                            let value = past + step;
+                            target.entry(key(value)).or_insert((value, 0.)).1 += multiplicity;
+                        }
+                    }
+                });
+            }
+            // with one column left a node has a single completion, so nothing reaches the last stage
+            stage = next
+                .into_iter()
+                .map(|(node, pasts)| (node, Pasts::new(pasts.into_values().collect(), constant)))
+                .collect();
+        }
+
+        TestResult {
+            statistic: (constant + observed_past).exp(),
+            df: None,
+            p_value: p_value.min(1.),
+        }
+    }
+
+    /// mcnemar's test of symmetry for paired categorical data.
+    /// tables larger than 2×2 use bowker's generalization.
+    /// `correction` applies the continuity correction, which only affects 2×2 tables
+    pub fn mcnemar(&self, correction: bool) -> TestResult {
+        let k = self.n_rows();
+        assert_eq!(k, self.n_columns(), "mcnemar needs a square table");
+        let correction = if correction && k == 2 { 1. } else { 0. };
+
+        let mut statistic = 0 as Num;
+        let mut df = 0 as Num;
+        for i in 0..k {
+            for j in i + 1..k {
+                let (b, c) = (self.observed[i][j], self.observed[j][i]);
+                if b + c > 0. {
+                    statistic += ((b - c).abs() - correction).max(0.).powi(2) / (b + c);
+                }
+                df += 1.;
+            }
+        }
+
+        TestResult {
+            statistic,
+            df: Some(df),
+            p_value: ChiSquared::new(df).sf(statistic),
+        }
+    }
+
+    /// phi coefficient of a 2×2 table, signed by the direction of the association
+    pub fn phi(&self) -> Num {
+        assert!(self.is_2x2(), "phi needs a 2x2 table");
+        let o = &self.observed;
+        let rows = self.row_totals();
+        let columns = self.column_totals();
+        (o[0][0] * o[1][1] - o[0][1] * o[1][0])
+            / (rows[0] * rows[1] * columns[0] * columns[1]).sqrt()
+    }
+
+    /// cramér's V, the chi-square statistic scaled to `[0, 1]`
+    pub fn cramers_v(&self) -> Num {
+        let statistic = self.chi_square(false).statistic;
+        let smaller = self.n_rows().min(self.n_columns()) as Num;
+        (statistic / (self.total() * (smaller - 1.))).sqrt()
+    }
+
+    /// pearson's contingency coefficient `sqrt(chi2 / (chi2 + n))`
+    pub fn contingency_coefficient(&self) -> Num {
+        let statistic = self.chi_square(false).statistic;
+        (statistic / (statistic + self.total())).sqrt()
+    }
+}
+
+fn label_index<T: Ord>(labels: &[T]) -> BTreeMap<&T, usize> {
+    let mut index = BTreeMap::new();
+    labels.iter().for_each(|l| {
+        index.entry(l).or_insert(0);
+    });
+    index.values_mut().enumerate().for_each(|(i, v)| *v = i);
+    index
+}
+
+fn ln_factorial(n: Num) -> Num {
+    ln_gamma(n + 1.)
+}
+
+/// `ln(sum(prod(1 / x_ij!)))` over every table with the given row and column totals, which is
+/// `ln(n! / (prod(rows!) prod(columns!)))`
+fn completions(rows: &[u64], columns: &[u64], ln_factorials: &[Num]) -> Num {
+    let n = rows.iter().sum::<u64>();
+    ln_factorials[n as usize]
+        - rows.iter().map(|r| ln_factorials[*r as usize]).sum::<Num>()
+        - columns
+            .iter()
+            .map(|c| ln_factorials[*c as usize])
+            .sum::<Num>()
+}
+
+/// paths reaching a node of the network, sorted by their past `-sum(ln x_ij!)`, with the running
+/// sum of their probabilities so far
+struct Pasts {
+    /// past value and number of paths with it
+    paths: Vec<(Num, Num)>,
+    /// `cumulative[i]` is the sum of `multiplicity * exp(constant + past)` over the first `i` paths
+    cumulative: Vec<Num>,
+}
+
+impl Pasts {
+    fn new(mut paths: Vec<(Num, Num)>, constant: Num) -> Self {
+        paths.sort_by_key(|(past, _)| OrdFloat(*past));
+        let mut cumulative = Vec::with_capacity(paths.len() + 1);
+        cumulative.push(0.);
+        for (past, multiplicity) in paths.iter() {
+            let last = cumulative[cumulative.len() - 1];
+            cumulative.push(last + multiplicity * (constant + past).exp());
+        }
+        Pasts { paths, cumulative }
+    }
+
+    /// number of paths whose past is at most `limit`
+    fn below(&self, limit: Num) -> usize {
+        self.paths.partition_point(|(past, _)| *past <= limit)
+    }
+
+    /// probability so far of the first `count` paths
+    fn probability(&self, count: usize) -> Num {
+        self.cumulative[count]
+    }
+}
+
+/// lower and upper bounds on the shortest and longest paths from a node to the end of the network,
+/// the smallest and largest `-sum(ln x_ij!)` over the tables with the node's remaining row totals
+/// and the later column totals. each relaxes the table into independent rows, or independent columns,
+/// that only keep their own totals and the caps the other margin sets, and the tighter of the two is kept.
+/// with two columns left the lengths are exact
+fn path_bounds(rows: &[u64], columns: &[u64], ln_factorials: &[Num]) -> (Num, Num) {
+    if columns.len() == 2 {
+        // the split of the first column fixes the second, so the exact lengths are cheap
+        let mut lengths = (Num::INFINITY, Num::NEG_INFINITY);
+        for_each_column(rows, columns[0], &mut |entries: &[u64]| {
+            let length = -rows
+                .iter()
+                .zip(entries.iter())
+                .map(|(r, x)| ln_factorials[*x as usize] + ln_factorials[(r - x) as usize])
+                .sum::<Num>();
+            lengths = (lengths.0.min(length), lengths.1.max(length));
+        });
+        return lengths;
+    }
+    let relax = |totals: &[u64], caps: &[u64]| {
+        let mut ascending = caps.to_vec();
+        ascending.sort_unstable();
+        totals
+            .iter()
+            .fold((0 as Num, 0 as Num), |(low, high), total| {
+                (
+                    low + concentrated(*total, &ascending, ln_factorials),
+                    high + spread(*total, &ascending, ln_factorials),
+                )
+            })
+    };
+    let (low_columns, high_columns) = relax(columns, rows);
+    let (low_rows, high_rows) = relax(rows, columns);
+    (low_columns.max(low_rows), high_columns.min(high_rows))
+}
+
+/// largest `-sum(ln x_i!)` of `total` split into cells under the ascending `caps`, from the most even split
+fn spread(total: u64, caps: &[u64], ln_factorials: &[Num]) -> Num {
+    let mut left = total;
+    let mut value = 0 as Num;
+    for (i, cap) in caps.iter().enumerate() {
+        let cells = (caps.len() - i) as u64;
+        if *cap * cells <= left {
+            value -= ln_factorials[*cap as usize];
+            left -= cap;
+        } else {
+            // the rest all take more than their share, which is split as evenly as possible
+            let (share, extra) = (left / cells, left % cells);
+            value -= (cells - extra) as Num * ln_factorials[share as usize];
+            if extra > 0 {
+                value -= extra as Num * ln_factorials[(share + 1) as usize];
+            }
+            break;
+        }
+    }
+    value
+}
+
+/// smallest `-sum(ln x_i!)` of `total` split into cells under the ascending `caps`, filling the largest first
+fn concentrated(total: u64, caps: &[u64], ln_factorials: &[Num]) -> Num {
+    let mut left = total;
+    let mut value = 0 as Num;
+    for cap in caps.iter().rev() {
+        let x = left.min(*cap);
+        value -= ln_factorials[x as usize];
+        left -= x;
+    }
+    value
+}
+
+/// calls `func` with every way of splitting `total` into the rows without exceeding `remaining`
+fn for_each_column<F>(remaining: &[u64], total: u64, func: &mut F)
+where
+    F: FnMut(&[u64]),
+{
+    fn fill<F: FnMut(&[u64])>(
+        remaining: &[u64],
+        capacity: &[u64],
+        left: u64,
+        entries: &mut Vec<u64>,
+        func: &mut F,
+    ) {
+        let i = entries.len();
+        if i == remaining.len() {
+            if left == 0 {
+                func(entries);
+            }
+            return;
+        }
+        // the rows after this one must be able to take what is left
+        let lowest = left.saturating_sub(capacity[i + 1]);
+        for x in lowest..=left.min(remaining[i]) {
+            entries.push(x);
+            fill(remaining, capacity, left - x, entries, func);
+            entries.pop();
+        }
+    }
+
+    // capacity[i] is the sum of remaining[i..]
+    let mut capacity = vec![0; remaining.len() + 1];
+    for i in (0..remaining.len()).rev() {
+        capacity[i] = capacity[i + 1] + remaining[i];
+    }
+    fill(
+        remaining,
+        &capacity,
+        total,
+        &mut Vec::with_capacity(remaining.len()),
+        func,
+    );
+}
+
+/// cochran–mantel–haenszel test of conditional independence in a set of 2×2 tables,
+/// one for each stratum. `correction` applies the continuity correction
+pub fn cochran_mantel_haenszel(strata: &[ContingencyTable], correction: bool) -> TestResult {
+    let mut difference = 0 as Num;
+    let mut variance = 0 as Num;
+    for table in strata.iter() {
+        let rows = table.row_totals();
+        let columns = table.column_totals();
+        let n = table.total();
+        difference += table.observed[0][0] - rows[0] * columns[0] / n;
+        variance += rows[0] * rows[1] * columns[0] * columns[1] / (n * n * (n - 1.));
+    }
+
+    let correction = if correction { 0.5 } else { 0. };
+    let statistic = (difference.abs() - correction).max(0.).powi(2) / variance;
+    TestResult {
+        statistic,
+        df: Some(1.),
+        p_value: ChiSquared::new(1.).sf(statistic),
+    }
+}
+
+/// mantel–haenszel estimate of the odds ratio shared by a set of 2×2 tables
+pub fn mantel_haenszel_odds_ratio(strata: &[ContingencyTable]) -> Num {
+    let (numerator, denominator) = strata.iter().fold((0., 0.), |(num, den), table| {
+        let o = &table.observed;
+        let n = table.total();
+        (num + o[0][0] * o[1][1] / n, den + o[0][1] * o[1][0] / n)
+    });
+    numerator / denominator
+}
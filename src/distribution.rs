@@ -0,0 +1,922 @@
+//! probability distributions used by the inference functions of the library
+use crate::generic_types::Num;
+
+/// special functions the distributions are built on
+pub mod special {
+    use super::*;
+
+    const EPSILON: Num = 1e-15;
+    const TINY: Num = 1e-300;
+    const MAX_ITERATION: usize = 500;
+
+    const LANCZOS_G: Num = 7.;
+    const LANCZOS_COEFFICIENTS: [Num; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    /// natural logarithm of the gamma function, computed with the lanczos approximation
+    pub fn ln_gamma(x: Num) -> Num {
+        if x < 0.5 {
+            // reflection formula
+            let pi = std::f64::consts::PI;
+            return (pi / (pi * x).sin()).abs().ln() - ln_gamma(1. - x);
+        }
+
+        let x = x - 1.;
+        let mut a = LANCZOS_COEFFICIENTS[0];
+        let t = x + LANCZOS_G + 0.5;
+        for (i, c) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+            a += c / (x + i as Num);
+        }
+
+        0.5 * (2. * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+    }
+
+    /// natural logarithm of the beta function
+    pub fn ln_beta(a: Num, b: Num) -> Num {
+        ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+    }
+
+    /// natural logarithm of the binomial coefficient `n choose k`
+    pub fn ln_choose(n: Num, k: Num) -> Num {
+        ln_gamma(n + 1.) - ln_gamma(k + 1.) - ln_gamma(n - k + 1.)
+    }
+
+    /// regularized lower incomplete gamma function P(a, x)
+    pub fn gamma_p(a: Num, x: Num) -> Num {
+        if x <= 0. {
+            0.
+        } else if x < a + 1. {
+            gamma_series(a, x)
+        } else {
+            1. - gamma_continued_fraction(a, x)
+        }
+    }
+
+    /// regularized upper incomplete gamma function Q(a, x)
+    pub fn gamma_q(a: Num, x: Num) -> Num {
+        if x <= 0. {
+            1.
+        } else if x < a + 1. {
+            1. - gamma_series(a, x)
+        } else {
+            gamma_continued_fraction(a, x)
+        }
+    }
+
+    fn gamma_series(a: Num, x: Num) -> Num {
+        let mut ap = a;
+        let mut delta = 1. / a;
+        let mut sum = delta;
+        for _ in 0..MAX_ITERATION {
+            ap += 1.;
+            delta *= x / ap;
+            sum += delta;
+            if delta.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+    }
+
+    fn gamma_continued_fraction(a: Num, x: Num) -> Num {
+        let mut b = x + 1. - a;
+        let mut c = 1. / TINY;
+        let mut d = 1. / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATION {
+            let an = -(i as Num) * (i as Num - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.).abs() < EPSILON {
+                break;
+            }
+        }
+        (-x + a * x.ln() - ln_gamma(a)).exp() * h
+    }
+
+    /// regularized incomplete beta function I_x(a, b)
+    pub fn beta_regularized(x: Num, a: Num, b: Num) -> Num {
+        if x <= 0. {
+            return 0.;
+        }
+        if x >= 1. {
+            return 1.;
+        }
+
+        let front = (a * x.ln() + b * (1. - x).ln() - ln_beta(a, b)).exp();
+        // the continued fraction converges quickly only on one side of the mean
+        if x < (a + 1.) / (a + b + 2.) {
+            front * beta_continued_fraction(x, a, b) / a
+        } else {
+            1. - front * beta_continued_fraction(1. - x, b, a) / b
+        }
+    }
+
+    fn beta_continued_fraction(x: Num, a: Num, b: Num) -> Num {
+        let qab = a + b;
+        let qap = a + 1.;
+        let qam = a - 1.;
+        let mut c = 1.;
+        let mut d = 1. - qab * x / qap;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        d = 1. / d;
+        let mut h = d;
+
+        for m in 1..MAX_ITERATION {
+            let m = m as Num;
+            let m2 = 2. * m;
+
+            let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+            d = 1. + aa * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1. + aa / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1. / d;
+            h *= d * c;
+
+            let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+            d = 1. + aa * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1. + aa / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.).abs() < EPSILON {
+                break;
+            }
+        }
+        h
+    }
+
+    /// complementary error function
+    pub fn erfc(x: Num) -> Num {
+        if x < 0. {
+            2. - erfc(-x)
+        } else {
+            gamma_q(0.5, x * x)
+        }
+    }
+
+    /// error function
+    pub fn erf(x: Num) -> Num {
+        1. - erfc(x)
+    }
+
+    /// nodes and weights of the gauss–legendre rule of `order` points on `[-1, 1]`
+    pub(crate) fn gauss_legendre(order: usize) -> Vec<(Num, Num)> {
+        let n = order as Num;
+        (0..order)
+            .map(|i| {
+                // newton's method on the legendre polynomial, starting from a chebyshev like guess
+                let mut x = (std::f64::consts::PI * (i as Num + 0.75) / (n + 0.5)).cos();
+                let mut derivative = 1.;
+                for _ in 0..100 {
+                    let (mut p0, mut p1) = (1 as Num, x);
+                    for j in 2..=order {
+                        let j = j as Num;
+                        let p2 = ((2. * j - 1.) * x * p1 - (j - 1.) * p0) / j;
+                        p0 = p1;
+                        p1 = p2;
+                    }
+                    derivative = n * (x * p1 - p0) / (x * x - 1.);
+                    let step = p1 / derivative;
+                    x -= step;
+                    if step.abs() < EPSILON {
+                        break;
+                    }
+                }
+                (x, 2. / ((1. - x * x) * derivative * derivative))
+            })
+            .collect()
+    }
+
+    /// integrates `func` over `[lower, upper]` with a composite gauss–legendre rule
+    /// made of `panels` equally wide panels
+    pub(crate) fn integrate<F>(
+        func: F,
+        lower: Num,
+        upper: Num,
+        panels: usize,
+        rule: &[(Num, Num)],
+    ) -> Num
+    where
+        F: Fn(Num) -> Num,
+    {
+        let width = (upper - lower) / panels as Num;
+        (0..panels).fold(0 as Num, |acc, i| {
+            let center = lower + (i as Num + 0.5) * width;
+            let half = width / 2.;
+            acc + rule
+                .iter()
+                .fold(0 as Num, |acc, (x, w)| acc + w * func(center + half * x))
+                * half
+        })
+    }
+
+    /// finds x where `func(x) == target` for a monotonically increasing `func`.
+    /// the bracket `[lower, upper]` is widened until it contains the root
+    pub(crate) fn invert_monotone<F>(func: F, target: Num, lower: Num, upper: Num) -> Num
+    where
+        F: Fn(Num) -> Num,
+    {
+        let (mut lower, mut upper) = (lower, upper);
+        for _ in 0..MAX_ITERATION {
+            if func(lower) <= target {
+                break;
+            }
+            lower -= (upper - lower).max(1.);
+        }
+        for _ in 0..MAX_ITERATION {
+            if func(upper) >= target {
+                break;
+            }
+            upper += (upper - lower).max(1.);
+        }
+
+        // illinois variant of the regula falsi
+        let mut f_lower = func(lower) - target;
+        let mut f_upper = func(upper) - target;
+        let mut side = 0;
+        let mut x = 0.5 * (lower + upper);
+        for _ in 0..200 {
+            x = if f_upper == f_lower {
+                0.5 * (lower + upper)
+            } else {
+                (lower * f_upper - upper * f_lower) / (f_upper - f_lower)
+            };
+            let fx = func(x) - target;
+            if fx == 0. || upper - lower <= EPSILON * x.abs().max(1.) {
+                break;
+            }
+
+            if fx > 0. {
+                upper = x;
+                f_upper = fx;
+                if side == -1 {
+                    f_lower /= 2.;
+                }
+                side = -1;
+            } else {
+                lower = x;
+                f_lower = fx;
+                if side == 1 {
+                    f_upper /= 2.;
+                }
+                side = 1;
+            }
+        }
+        x
+    }
+}
+
+use special::*;
+
+/// normal distribution with mean `mu` and standard deviation `sigma`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    pub mu: Num,
+    pub sigma: Num,
+}
+
+impl Normal {
+    pub fn new(mu: Num, sigma: Num) -> Self {
+        Normal { mu, sigma }
+    }
+
+    #[inline]
+    pub fn standard() -> Self {
+        Normal::new(0., 1.)
+    }
+
+    pub fn pdf(&self, x: Num) -> Num {
+        let z = (x - self.mu) / self.sigma;
+        (-0.5 * z * z).exp() / (self.sigma * (2. * std::f64::consts::PI).sqrt())
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        let z = (x - self.mu) / self.sigma;
+        0.5 * erfc(-z / std::f64::consts::SQRT_2)
+    }
+
+    /// survival function, `1 - cdf(x)` without the loss of precision in the upper tail
+    pub fn sf(&self, x: Num) -> Num {
+        let z = (x - self.mu) / self.sigma;
+        0.5 * erfc(z / std::f64::consts::SQRT_2)
+    }
+
+    /// inverse of the cdf
+    pub fn quantile(&self, p: Num) -> Num {
+        self.mu + self.sigma * standard_normal_quantile(p)
+    }
+}
+
+/// acklam's rational approximation refined with a single halley step
+fn standard_normal_quantile(p: Num) -> Num {
+    if p <= 0. {
+        return Num::NEG_INFINITY;
+    }
+    if p >= 1. {
+        return Num::INFINITY;
+    }
+
+    const A: [Num; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [Num; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [Num; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [Num; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    let p_low = 0.024_25;
+
+    let x = if p < p_low {
+        let q = (-2. * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    } else if p <= 1. - p_low {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        let q = (-2. * (1. - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+
+    let e = 0.5 * erfc(-x / std::f64::consts::SQRT_2) - p;
+    let u = e * (2. * std::f64::consts::PI).sqrt() * (x * x / 2.).exp();
+    x - u / (1. + x * u / 2.)
+}
+
+/// student's t distribution with `df` degrees of freedom.
+/// infinite `df` falls back to the standard normal distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentT {
+    pub df: Num,
+}
+
+impl StudentT {
+    pub fn new(df: Num) -> Self {
+        StudentT { df }
+    }
+
+    pub fn pdf(&self, x: Num) -> Num {
+        if self.df.is_infinite() {
+            return Normal::standard().pdf(x);
+        }
+        let v = self.df;
+        let ln = ln_gamma((v + 1.) / 2.)
+            - ln_gamma(v / 2.)
+            - 0.5 * (v * std::f64::consts::PI).ln()
+            - (v + 1.) / 2. * (1. + x * x / v).ln();
+        ln.exp()
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        if self.df.is_infinite() {
+            return Normal::standard().cdf(x);
+        }
+        let v = self.df;
+        let tail = 0.5 * beta_regularized(v / (v + x * x), v / 2., 0.5);
+        if x > 0. {
+            1. - tail
+        } else {
+            tail
+        }
+    }
+
+    pub fn sf(&self, x: Num) -> Num {
+        self.cdf(-x)
+    }
+
+    pub fn quantile(&self, p: Num) -> Num {
+        if self.df.is_infinite() {
+            return Normal::standard().quantile(p);
+        }
+        if p <= 0. {
+            return Num::NEG_INFINITY;
+        }
+        if p >= 1. {
+            return Num::INFINITY;
+        }
+        let guess = Normal::standard().quantile(p);
+        invert_monotone(|x| self.cdf(x), p, guess - 1., guess + 1.)
+    }
+}
+
+/// chi-square distribution with `df` degrees of freedom
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared {
+    pub df: Num,
+}
+
+impl ChiSquared {
+    pub fn new(df: Num) -> Self {
+        ChiSquared { df }
+    }
+
+    pub fn pdf(&self, x: Num) -> Num {
+        if x < 0. {
+            return 0.;
+        }
+        let k = self.df / 2.;
+        ((k - 1.) * x.ln() - x / 2. - k * (2 as Num).ln() - ln_gamma(k)).exp()
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        gamma_p(self.df / 2., x / 2.)
+    }
+
+    pub fn sf(&self, x: Num) -> Num {
+        gamma_q(self.df / 2., x / 2.)
+    }
+
+    pub fn quantile(&self, p: Num) -> Num {
+        if p <= 0. {
+            return 0.;
+        }
+        if p >= 1. {
+            return Num::INFINITY;
+        }
+        invert_monotone(|x| self.cdf(x), p, 0., self.df.max(1.)).max(0.)
+    }
+}
+
+/// binomial distribution of the number of successes in `n` trials with success probability `p`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial {
+    pub n: u64,
+    pub p: Num,
+}
+
+impl Binomial {
+    pub fn new(n: u64, p: Num) -> Self {
+        Binomial { n, p }
+    }
+
+    pub fn mean(&self) -> Num {
+        self.n as Num * self.p
+    }
+
+    pub fn variance(&self) -> Num {
+        self.n as Num * self.p * (1. - self.p)
+    }
+
+    pub fn pmf(&self, k: u64) -> Num {
+        if k > self.n {
+            return 0.;
+        }
+        if self.p == 0. {
+            return if k == 0 { 1. } else { 0. };
+        }
+        if self.p == 1. {
+            return if k == self.n { 1. } else { 0. };
+        }
+        let (n, k) = (self.n as Num, k as Num);
+        (ln_choose(n, k) + k * self.p.ln() + (n - k) * (1. - self.p).ln()).exp()
+    }
+
+    /// P(X <= k)
+    pub fn cdf(&self, k: u64) -> Num {
+        if k >= self.n {
+            return 1.;
+        }
+        // I_{1-p}(n - k, k + 1)
+        beta_regularized(1. - self.p, (self.n - k) as Num, (k + 1) as Num)
+    }
+
+    /// P(X > k)
+    pub fn sf(&self, k: u64) -> Num {
+        if k >= self.n {
+            return 0.;
+        }
+        beta_regularized(self.p, (k + 1) as Num, (self.n - k) as Num)
+    }
+}
+
+/// fisher–snedecor F distribution with `df1` and `df2` degrees of freedom
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FisherF {
+    pub df1: Num,
+    pub df2: Num,
+}
+
+impl FisherF {
+    pub fn new(df1: Num, df2: Num) -> Self {
+        FisherF { df1, df2 }
+    }
+
+    pub fn pdf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 0.;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        let ln = 0.5 * (d1 * (d1 * x).ln() + d2 * d2.ln() - (d1 + d2) * (d1 * x + d2).ln())
+            - x.ln()
+            - ln_beta(d1 / 2., d2 / 2.);
+        ln.exp()
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 0.;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        beta_regularized(d1 * x / (d1 * x + d2), d1 / 2., d2 / 2.)
+    }
+
+    pub fn sf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 1.;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        beta_regularized(d2 / (d2 + d1 * x), d2 / 2., d1 / 2.)
+    }
+
+    pub fn quantile(&self, p: Num) -> Num {
+        if p <= 0. {
+            return 0.;
+        }
+        if p >= 1. {
+            return Num::INFINITY;
+        }
+        invert_monotone(|x| self.cdf(x), p, 0., 2.).max(0.)
+    }
+}
+
+/// distribution of the studentized range of `k` normal samples
+/// whose standard deviation is estimated with `df` degrees of freedom.
+/// used by tukey's HSD and the games–howell test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentizedRange {
+    pub k: Num,
+    pub df: Num,
+}
+
+impl StudentizedRange {
+    pub fn new(k: Num, df: Num) -> Self {
+        StudentizedRange { k, df }
+    }
+
+    /// cdf of the range when the standard deviation is known
+    fn range_cdf(q: Num, k: Num, rule: &[(Num, Num)]) -> Num {
+        if q <= 0. {
+            return 0.;
+        }
+        let normal = Normal::standard();
+        let integrand = |z: Num| {
+            let inner = (normal.cdf(z) - normal.cdf(z - q)).max(0.);
+            normal.pdf(z) * inner.powf(k - 1.)
+        };
+        // the integrand vanishes outside of [-8, 8 + q]
+        let panels = 8 + q.ceil() as usize;
+        (k * integrate(integrand, -8., 8. + q, panels, rule)).min(1.)
+    }
+
+    pub fn cdf(&self, q: Num) -> Num {
+        if q <= 0. {
+            return 0.;
+        }
+        let rule = gauss_legendre(16);
+        if self.df.is_infinite() {
+            return Self::range_cdf(q, self.k, &rule);
+        }
+
+        // integrate over the distribution of s = sqrt(chi2(df) / df)
+        let v = self.df;
+        let ln_norm = v / 2. * v.ln() - ln_gamma(v / 2.) - (v / 2. - 1.) * (2 as Num).ln();
+        let density = |s: Num| {
+            if s <= 0. {
+                0.
+            } else {
+                (ln_norm + (v - 1.) * s.ln() - v * s * s / 2.).exp()
+            }
+        };
+        let chi = ChiSquared::new(v);
+        let lower = (chi.quantile(1e-12) / v).sqrt();
+        let upper = (chi.quantile(1. - 1e-12) / v).sqrt();
+        let integral = integrate(
+            |s| density(s) * Self::range_cdf(q * s, self.k, &rule),
+            lower,
+            upper,
+            12,
+            &rule,
+        );
+        integral.clamp(0., 1.)
+    }
+
+    pub fn sf(&self, q: Num) -> Num {
+        (1. - self.cdf(q)).max(0.)
+    }
+
+    pub fn quantile(&self, p: Num) -> Num {
+        if p <= 0. {
+            return 0.;
+        }
+        if p >= 1. {
+            return Num::INFINITY;
+        }
+        invert_monotone(|x| self.cdf(x), p, 0., 5.)
+    }
+}
+
+/// hypergeometric distribution of the number of successes in `draws` draws without replacement
+/// from a population of `population` items that contains `successes` successes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hypergeometric {
+    pub population: u64,
+    pub successes: u64,
+    pub draws: u64,
+}
+
+impl Hypergeometric {
+    pub fn new(population: u64, successes: u64, draws: u64) -> Self {
+        Hypergeometric {
+            population,
+            successes,
+            draws,
+        }
+    }
+
+    /// smallest possible number of successes
+    pub fn min(&self) -> u64 {
+        (self.draws + self.successes).saturating_sub(self.population)
+    }
+
+    /// largest possible number of successes
+    pub fn max(&self) -> u64 {
+        self.draws.min(self.successes)
+    }
+
+    pub fn pmf(&self, k: u64) -> Num {
+        if k < self.min() || k > self.max() {
+            return 0.;
+        }
+        let (n, s, d, k) = (
+            self.population as Num,
+            self.successes as Num,
+            self.draws as Num,
+            k as Num,
+        );
+        (ln_choose(s, k) + ln_choose(n - s, d - k) - ln_choose(n, d)).exp()
+    }
+
+    /// P(X <= k)
+    pub fn cdf(&self, k: u64) -> Num {
+        (self.min()..=k.min(self.max()))
+            .map(|i| self.pmf(i))
+            .sum::<Num>()
+            .min(1.)
+    }
+
+    /// P(X > k)
+    pub fn sf(&self, k: u64) -> Num {
+        if k >= self.max() {
+            return 0.;
+        }
+        ((k + 1).max(self.min())..=self.max())
+            .map(|i| self.pmf(i))
+            .sum::<Num>()
+            .min(1.)
+    }
+}
+
+/// beta distribution with shape parameters `a` and `b`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta {
+    pub a: Num,
+    pub b: Num,
+}
+
+impl Beta {
+    pub fn new(a: Num, b: Num) -> Self {
+        Beta { a, b }
+    }
+
+    pub fn pdf(&self, x: Num) -> Num {
+        if !(0. ..=1.).contains(&x) {
+            return 0.;
+        }
+        ((self.a - 1.) * x.ln() + (self.b - 1.) * (1. - x).ln() - ln_beta(self.a, self.b)).exp()
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        beta_regularized(x, self.a, self.b)
+    }
+
+    pub fn quantile(&self, p: Num) -> Num {
+        if p <= 0. {
+            return 0.;
+        }
+        if p >= 1. {
+            return 1.;
+        }
+        invert_monotone(|x| self.cdf(x), p, 0., 1.).clamp(0., 1.)
+    }
+}
+
+/// terms of the poisson mixtures below are summed until they fall under this share of the total
+const MIXTURE_EPSILON: Num = 1e-14;
+
+/// `sum_j Poisson(j; mean) * term(j)`, summed outwards from the mode of the poisson weights
+fn poisson_mixture<F>(mean: Num, term: F) -> Num
+where
+    F: Fn(Num) -> Num,
+{
+    if mean <= 0. {
+        return term(0.);
+    }
+    let mode = mean.floor();
+    let weight = |j: Num| (-mean + j * mean.ln() - ln_gamma(j + 1.)).exp();
+
+    let mut total = 0 as Num;
+    let mut j = mode;
+    loop {
+        let w = weight(j);
+        total += w * term(j);
+        if (w < MIXTURE_EPSILON && j > mean) || j > mode + 10_000. {
+            break;
+        }
+        j += 1.;
+    }
+    let mut j = mode - 1.;
+    while j >= 0. {
+        let w = weight(j);
+        total += w * term(j);
+        if w < MIXTURE_EPSILON {
+            break;
+        }
+        j -= 1.;
+    }
+    total
+}
+
+/// noncentral chi-square distribution with noncentrality parameter `ncp`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoncentralChiSquared {
+    pub df: Num,
+    pub ncp: Num,
+}
+
+impl NoncentralChiSquared {
+    pub fn new(df: Num, ncp: Num) -> Self {
+        NoncentralChiSquared { df, ncp }
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 0.;
+        }
+        poisson_mixture(self.ncp / 2., |j| ChiSquared::new(self.df + 2. * j).cdf(x)).clamp(0., 1.)
+    }
+
+    pub fn sf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 1.;
+        }
+        poisson_mixture(self.ncp / 2., |j| ChiSquared::new(self.df + 2. * j).sf(x)).clamp(0., 1.)
+    }
+}
+
+/// noncentral F distribution with noncentrality parameter `ncp`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoncentralF {
+    pub df1: Num,
+    pub df2: Num,
+    pub ncp: Num,
+}
+
+impl NoncentralF {
+    pub fn new(df1: Num, df2: Num, ncp: Num) -> Self {
+        NoncentralF { df1, df2, ncp }
+    }
+
+    pub fn cdf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 0.;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        let y = d1 * x / (d1 * x + d2);
+        poisson_mixture(self.ncp / 2., |j| beta_regularized(y, d1 / 2. + j, d2 / 2.)).clamp(0., 1.)
+    }
+
+    pub fn sf(&self, x: Num) -> Num {
+        if x <= 0. {
+            return 1.;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        let y = d2 / (d1 * x + d2);
+        poisson_mixture(self.ncp / 2., |j| beta_regularized(y, d2 / 2., d1 / 2. + j)).clamp(0., 1.)
+    }
+}
+
+/// noncentral t distribution with noncentrality parameter `ncp`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoncentralT {
+    pub df: Num,
+    pub ncp: Num,
+}
+
+impl NoncentralT {
+    pub fn new(df: Num, ncp: Num) -> Self {
+        NoncentralT { df, ncp }
+    }
+
+    /// lenth's algorithm (AS 243) for `t >= 0`
+    fn upper_half_cdf(t: Num, df: Num, ncp: Num) -> Num {
+        let x = t * t / (t * t + df);
+        let lambda = ncp * ncp / 2.;
+        let normal = Normal::standard();
+
+        // the series mixes the `j + 1/2` and `j + 1` incomplete beta functions
+        let mut total = 0 as Num;
+        let mut j = 0 as Num;
+        loop {
+            let ln_weight = -lambda + j * lambda.max(Num::MIN_POSITIVE).ln();
+            let p = (ln_weight - ln_gamma(j + 1.)).exp();
+            let q = ncp / std::f64::consts::SQRT_2 * (ln_weight - ln_gamma(j + 1.5)).exp();
+            let term = p * beta_regularized(x, j + 0.5, df / 2.)
+                + q * beta_regularized(x, j + 1., df / 2.);
+            total += term;
+            if (j > lambda && term.abs() < MIXTURE_EPSILON) || j > lambda + 10_000. {
+                break;
+            }
+            j += 1.;
+        }
+        (normal.cdf(-ncp) + total / 2.).clamp(0., 1.)
+    }
+
+    pub fn cdf(&self, t: Num) -> Num {
+        if self.ncp == 0. {
+            return StudentT::new(self.df).cdf(t);
+        }
+        if t >= 0. {
+            Self::upper_half_cdf(t, self.df, self.ncp)
+        } else {
+            1. - Self::upper_half_cdf(-t, self.df, -self.ncp)
+        }
+    }
+
+    pub fn sf(&self, t: Num) -> Num {
+        if self.ncp == 0. {
+            return StudentT::new(self.df).sf(t);
+        }
+        if t >= 0. {
+            1. - Self::upper_half_cdf(t, self.df, self.ncp)
+        } else {
+            Self::upper_half_cdf(-t, self.df, -self.ncp)
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! effect size measures to report next to the hypothesis tests
+use crate::anova::AnovaTable;
+use crate::categorical::ContingencyTable;
+use crate::distribution::Normal;
+use crate::estimate::Estimate;
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::Alternative;
+use crate::nonparametric::mann_whitney_u;
+use crate::uni_dimentional::{mean, standard_deviation_sample};
+
+fn z_value(level: Num) -> Num {
+    Normal::standard().quantile(1. - (1. - level) / 2.)
+}
+
+fn pooled_standard_deviation(input1: &Input, input2: &Input) -> Num {
+    let n1 = input1.len() as Num;
+    let n2 = input2.len() as Num;
+    let s1 = standard_deviation_sample(input1);
+    let s2 = standard_deviation_sample(input2);
+    (((n1 - 1.) * s1 * s1 + (n2 - 1.) * s2 * s2) / (n1 + n2 - 2.)).sqrt()
+}
+
+/// large sample standard error of cohen's d
+fn cohens_d_standard_error(d: Num, n1: Num, n2: Num) -> Num {
+    ((n1 + n2) / (n1 * n2) + d * d / (2. * (n1 + n2))).sqrt()
+}
+
+/// cohen's d, the difference of means divided by the pooled standard deviation.
+/// the confidence interval uses the normal approximation of its standard error
+pub fn cohens_d(input1: &Input, input2: &Input, level: Num) -> Estimate {
+    let n1 = input1.len() as Num;
+    let n2 = input2.len() as Num;
+    let d = (mean(input1) - mean(input2)) / pooled_standard_deviation(input1, input2);
+    let margin = z_value(level) * cohens_d_standard_error(d, n1, n2);
+
+    Estimate {
+        value: d,
+        lower: d - margin,
+        upper: d + margin,
+        level,
+    }
+}
+
+/// hedges' g, cohen's d corrected for its bias in small samples
+pub fn hedges_g(input1: &Input, input2: &Input, level: Num) -> Estimate {
+    let n1 = input1.len() as Num;
+    let n2 = input2.len() as Num;
+    let correction = 1. - 3. / (4. * (n1 + n2) - 9.);
+    let d = cohens_d(input1, input2, level);
+
+    Estimate {
+        value: d.value * correction,
+        lower: d.lower * correction,
+        upper: d.upper * correction,
+        level,
+    }
+}
+
+/// glass's delta, the difference of means divided by the standard deviation of the `control` group only
+pub fn glass_delta(input: &Input, control: &Input) -> Num {
+    (mean(input) - mean(control)) / standard_deviation_sample(control)
+}
+
+/// cliff's delta, `P(X > Y) - P(X < Y)` for X from `input1` and Y from `input2`
+pub fn cliffs_delta(input1: &Input, input2: &Input) -> Num {
+    let dominance = input1.iter().fold(0 as Num, |acc, x| {
+        acc + input2.iter().fold(0 as Num, |acc, y| {
+            if x > y {
+                acc + 1.
+            } else if x < y {
+                acc - 1.
+            } else {
+                acc
+            }
+        })
+    });
+    dominance / (input1.len() * input2.len()) as Num
+}
+
+/// rank-biserial correlation of the mann–whitney U test, `2 U / (n1 n2) - 1`
+pub fn rank_biserial(input1: &Input, input2: &Input) -> Num {
+    let u = mann_whitney_u(input1, input2, Alternative::TwoSided).statistic;
+    2. * u / (input1.len() * input2.len()) as Num - 1.
+}
+
+/// eta squared of `source`, its share of the total sum of squares
+pub fn eta_squared(table: &AnovaTable, source: &str) -> Num {
+    table.row(source).unwrap().sum_of_squares / table.total_sum_of_squares
+}
+
+/// partial eta squared of `source`, `SS_effect / (SS_effect + SS_residuals)`
+pub fn partial_eta_squared(table: &AnovaTable, source: &str) -> Num {
+    let effect = table.row(source).unwrap().sum_of_squares;
+    effect / (effect + table.residuals().sum_of_squares)
+}
+
+/// omega squared of `source`, a less biased alternative to eta squared
+pub fn omega_squared(table: &AnovaTable, source: &str) -> Num {
+    let effect = table.row(source).unwrap();
+    let error = table.residuals().mean_square;
+    (effect.sum_of_squares - effect.df * error) / (table.total_sum_of_squares + error)
+}
+
+/// cells of a 2×2 table laid out as
+/// `[[exposed events, exposed non events], [unexposed events, unexposed non events]]`
+fn cells(table: &ContingencyTable) -> (Num, Num, Num, Num) {
+    let o = table.observed();
+    assert!(
+        o.len() == 2 && o.iter().all(|r| r.len() == 2),
+        "the effect sizes of a contingency table need a 2x2 table"
+    );
+    (o[0][0], o[0][1], o[1][0], o[1][1])
+}
+
+/// odds ratio of a 2×2 table with the wald interval of its logarithm.
+/// rows are exposed and unexposed, columns are event and non event
+pub fn odds_ratio(table: &ContingencyTable, level: Num) -> Estimate {
+    let (a, b, c, d) = cells(table);
+    let ratio = a * d / (b * c);
+    let log_ratio = ratio.ln();
+    let margin = z_value(level) * (1. / a + 1. / b + 1. / c + 1. / d).sqrt();
+
+    Estimate {
+        value: ratio,
+        lower: (log_ratio - margin).exp(),
+        upper: (log_ratio + margin).exp(),
+        level,
+    }
+}
+
+/// relative risk (risk ratio) of a 2×2 table with the wald interval of its logarithm.
+/// rows are exposed and unexposed, columns are event and non event
+pub fn relative_risk(table: &ContingencyTable, level: Num) -> Estimate {
+    let (a, b, c, d) = cells(table);
+    let ratio = (a / (a + b)) / (c / (c + d));
+    let log_ratio = ratio.ln();
+    let margin = z_value(level) * (1. / a - 1. / (a + b) + 1. / c - 1. / (c + d)).sqrt();
+
+    Estimate {
+        value: ratio,
+        lower: (log_ratio - margin).exp(),
+        upper: (log_ratio + margin).exp(),
+        level,
+    }
+}
+
+/// number needed to treat, `1 / (risk of the unexposed - risk of the exposed)`.
+/// rows are treated and control, columns are event and non event.
+/// a negative value is the number needed to harm
+pub fn number_needed_to_treat(table: &ContingencyTable) -> Num {
+    let (a, b, c, d) = cells(table);
+    1. / (c / (c + d) - a / (a + b))
+}
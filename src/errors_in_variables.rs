@@ -0,0 +1,135 @@
+//! regression when both variables are measured with error, and agreement between two measurement methods
+use crate::bi_dimentional::{sample_covariance, LinearRegression};
+use crate::distribution::{Normal, StudentT};
+use crate::estimate::Estimate;
+use crate::generic_types::{Input, Num};
+use crate::matrix::Matrix;
+use crate::uni_dimentional::{
+    mean, median, sample_variance, sort_float, standard_deviation_sample,
+};
+
+/// deming regression. `error_ratio` is the variance of the errors of y divided by that of the errors of x,
+/// `1` being orthogonal regression.
+/// gradient and intercept are NaN when x and y are uncorrelated, where the line is either flat or vertical
+pub fn deming_regression(input_x: &Input, input_y: &Input, error_ratio: Num) -> LinearRegression {
+    let sxx = sample_variance(input_x);
+    let syy = sample_variance(input_y);
+    let sxy = sample_covariance(input_x, input_y);
+    if sxy == 0. {
+        return LinearRegression::new(Num::NAN, Num::NAN, input_x, input_y);
+    }
+    let spread = syy - error_ratio * sxx;
+    let gradient = (spread + (spread * spread + 4. * error_ratio * sxy * sxy).sqrt()) / (2. * sxy);
+    let intercept = mean(input_y) - gradient * mean(input_x);
+    LinearRegression::new(intercept, gradient, input_x, input_y)
+}
+
+/// orthogonal regression, or total least squares, which minimizes the perpendicular distances to the line.
+/// the line runs through the means along the first right singular vector of the centered data
+pub fn orthogonal_regression(input_x: &Input, input_y: &Input) -> LinearRegression {
+    let (x_mean, y_mean) = (mean(input_x), mean(input_y));
+    let centered = input_x
+        .iter()
+        .zip(input_y.iter())
+        .map(|(x, y)| vec![x - x_mean, y - y_mean])
+        .collect::<Vec<Vec<Num>>>();
+    let rows = centered.iter().map(|r| &r[..]).collect::<Vec<&Input>>();
+    let (_, _, vectors) = Matrix::from_rows(&rows).svd();
+    let gradient = vectors[(1, 0)] / vectors[(0, 0)];
+    let intercept = y_mean - gradient * x_mean;
+    LinearRegression::new(intercept, gradient, input_x, input_y)
+}
+
+/// passing–bablok regression, a nonparametric method comparison fit that is symmetric in x and y.
+/// the gradient is the median of the pairwise slopes shifted by the number of slopes below -1,
+/// and the intercept the median of `y - gradient * x`.
+/// both are NaN when the shifted median falls outside the slopes, which happens when
+/// the methods are negatively related
+pub fn passing_bablok(input_x: &Input, input_y: &Input) -> LinearRegression {
+    let n = input_x.len();
+    let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n {
+        for j in i + 1..n {
+            let dx = input_x[j] - input_x[i];
+            let dy = input_y[j] - input_y[i];
+            // identical points carry no information and slopes of -1 would bias the estimate
+            if (dx == 0. && dy == 0.) || dy == -dx {
+                continue;
+            }
+            slopes.push(if dx == 0. {
+                dy.signum() * Num::INFINITY
+            } else {
+                dy / dx
+            });
+        }
+    }
+    let slopes = sort_float(&slopes);
+    let shift = slopes.iter().filter(|s| **s < -1.).count();
+    let count = slopes.len();
+    if count / 2 + shift >= count {
+        return LinearRegression::new(Num::NAN, Num::NAN, input_x, input_y);
+    }
+    let gradient = if count % 2 == 1 {
+        slopes[(count - 1) / 2 + shift]
+    } else {
+        (slopes[count / 2 - 1 + shift] + slopes[count / 2 + shift]) / 2.
+    };
+
+    let offsets = input_x
+        .iter()
+        .zip(input_y.iter())
+        .map(|(x, y)| y - gradient * x)
+        .collect::<Vec<Num>>();
+    let intercept = median(&sort_float(&offsets));
+    LinearRegression::new(intercept, gradient, input_x, input_y)
+}
+
+/// bland–altman analysis of the agreement between two methods measuring the same subjects
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlandAltman {
+    /// mean of `input1 - input2`, with a t confidence interval
+    pub bias: Estimate,
+    /// sample standard deviation of the differences
+    pub standard_deviation: Num,
+    /// range expected to hold the given share of the differences, `bias ± z * standard_deviation`
+    pub limits_of_agreement: (Num, Num),
+    /// mean of both measurements of every subject, the x axis of the plot
+    pub means: Vec<Num>,
+    /// `input1 - input2` of every subject, the y axis of the plot
+    pub differences: Vec<Num>,
+}
+
+/// bland–altman agreement analysis. `level` is used both for the limits of agreement, e.g. `0.95` for `±1.96` standard
+/// deviations, and for the confidence interval of the bias
+pub fn bland_altman(input1: &Input, input2: &Input, level: Num) -> BlandAltman {
+    let differences = input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(a, b)| a - b)
+        .collect::<Vec<Num>>();
+    let means = input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(a, b)| (a + b) / 2.)
+        .collect::<Vec<Num>>();
+    let n = differences.len() as Num;
+    let bias = mean(&differences);
+    let standard_deviation = standard_deviation_sample(&differences);
+
+    let tail = 1. - (1. - level) / 2.;
+    let z = Normal::standard().quantile(tail);
+    let margin = StudentT::new(n - 1.).quantile(tail) * standard_deviation / n.sqrt();
+
+    BlandAltman {
+        bias: Estimate {
+            value: bias,
+            lower: bias - margin,
+            upper: bias + margin,
+            level,
+        },
+        standard_deviation,
+        limits_of_agreement: (bias - z * standard_deviation, bias + z * standard_deviation),
+        means,
+        differences,
+    }
+}
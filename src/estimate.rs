@@ -0,0 +1,157 @@
+//! point estimates together with their confidence intervals
+use crate::bi_dimentional::{correlation, linear_regression};
+use crate::distribution::{Binomial, ChiSquared, Normal, StudentT};
+use crate::generic_types::{Input, Num};
+use crate::uni_dimentional::{mean, median, sample_variance};
+
+/// a point estimate with the two sided confidence interval `[lower, upper]` at `level`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub value: Num,
+    pub lower: Num,
+    pub upper: Num,
+    pub level: Num,
+}
+
+impl Estimate {
+    /// whether `x` lies inside of the confidence interval
+    pub fn contains(&self, x: Num) -> bool {
+        self.lower <= x && x <= self.upper
+    }
+
+    #[inline]
+    pub fn width(&self) -> Num {
+        self.upper - self.lower
+    }
+}
+
+/// t-based confidence interval of the mean
+pub fn mean_confidence_interval(input: &Input, level: Num) -> Estimate {
+    let n = input.len() as Num;
+    let value = mean(input);
+    let standard_error = (sample_variance(input) / n).sqrt();
+    let margin = StudentT::new(n - 1.).quantile(1. - (1. - level) / 2.) * standard_error;
+
+    Estimate {
+        value,
+        lower: value - margin,
+        upper: value + margin,
+        level,
+    }
+}
+
+/// chi-square based confidence interval of the sample variance. assumes normally distributed data
+pub fn variance_confidence_interval(input: &Input, level: Num) -> Estimate {
+    let df = input.len() as Num - 1.;
+    let value = sample_variance(input);
+    let chi = ChiSquared::new(df);
+    let alpha = 1. - level;
+
+    Estimate {
+        value,
+        lower: df * value / chi.quantile(1. - alpha / 2.),
+        upper: df * value / chi.quantile(alpha / 2.),
+        level,
+    }
+}
+
+/// confidence interval of the sample standard deviation, the square root of `variance_confidence_interval`
+pub fn standard_deviation_confidence_interval(input: &Input, level: Num) -> Estimate {
+    let variance = variance_confidence_interval(input, level);
+    Estimate {
+        value: variance.value.sqrt(),
+        lower: variance.lower.sqrt(),
+        upper: variance.upper.sqrt(),
+        level,
+    }
+}
+
+/// distribution free confidence interval of the `q` quantile from the order statistics.
+/// the number of values below the quantile is binomial, so the interval covers at least `level`.
+/// Input must be sorted or it will yeild a wrong result
+pub fn quantile_confidence_interval(input: &Input, q: Num, level: Num) -> Estimate {
+    let n = input.len();
+    let binomial = Binomial::new(n as u64, q);
+    let tail = (1. - level) / 2.;
+
+    // 1 based ranks of the order statistics bounding the interval
+    let lower = (1..=n)
+        .take_while(|j| binomial.cdf(*j as u64 - 1) <= tail)
+        .last()
+        .unwrap_or(1);
+    let upper = (1..=n)
+        .find(|j| binomial.sf(*j as u64 - 1) <= tail)
+        .unwrap_or(n);
+
+    Estimate {
+        value: interpolated_quantile(input, q),
+        lower: input[lower - 1],
+        upper: input[upper - 1],
+        level,
+    }
+}
+
+/// confidence interval of the median from the order statistics.
+/// Input must be sorted or it will yeild a wrong result
+pub fn median_confidence_interval(input: &Input, level: Num) -> Estimate {
+    Estimate {
+        value: median(input),
+        ..quantile_confidence_interval(input, 0.5, level)
+    }
+}
+
+/// quantile that linearly interpolates between the closest ranks of the sorted input
+pub(crate) fn interpolated_quantile(input: &Input, q: Num) -> Num {
+    let position = (input.len() - 1) as Num * q;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    input[below] + (input[above] - input[below]) * (position - below as Num)
+}
+
+/// confidence interval of the pearson correlation using fisher's z transformation
+pub fn correlation_confidence_interval(input1: &Input, input2: &Input, level: Num) -> Estimate {
+    let value = correlation(input1, input2);
+    let standard_error = 1. / (input1.len() as Num - 3.).sqrt();
+    let margin = Normal::standard().quantile(1. - (1. - level) / 2.) * standard_error;
+    let z = value.atanh();
+
+    Estimate {
+        value,
+        lower: (z - margin).tanh(),
+        upper: (z + margin).tanh(),
+        level,
+    }
+}
+
+/// t-based confidence intervals of the intercept and the gradient of `linear_regression`.
+/// returns `(intercept, gradient)`
+pub fn regression_confidence_intervals(
+    input_x: &Input,
+    input_y: &Input,
+    level: Num,
+) -> (Estimate, Estimate) {
+    let model = linear_regression(input_x, input_y);
+    let n = input_x.len() as Num;
+    let x_mean = mean(input_x);
+
+    let sxx = input_x
+        .iter()
+        .fold(0 as Num, |acc, x| acc + (x - x_mean).powi(2));
+    let sse = model.residuals.iter().fold(0 as Num, |acc, r| acc + r * r);
+    let residual_variance = sse / (n - 2.);
+    let t = StudentT::new(n - 2.).quantile(1. - (1. - level) / 2.);
+
+    let gradient_error = (residual_variance / sxx).sqrt();
+    let intercept_error = (residual_variance * (1. / n + x_mean * x_mean / sxx)).sqrt();
+
+    let interval = |value: Num, standard_error: Num| Estimate {
+        value,
+        lower: value - t * standard_error,
+        upper: value + t * standard_error,
+        level,
+    };
+    (
+        interval(model.intercept, intercept_error),
+        interval(model.gradient, gradient_error),
+    )
+}
@@ -7,7 +7,7 @@ pub type Int = i64;
 pub type Input = [Num];
 pub type IntInput = [Int];
 
-#[derive(Default, PartialOrd, PartialEq, Copy, Clone)]
+#[derive(Default, PartialEq, Copy, Clone)]
 pub struct OrdFloat(pub f64);
 impl Eq for OrdFloat {}
 impl Ord for OrdFloat {
@@ -21,6 +21,11 @@ impl Ord for OrdFloat {
         }
     }
 }
+impl PartialOrd for OrdFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 impl From<OrdFloat> for f64 {
     fn from(o: OrdFloat) -> Self {
         o.0
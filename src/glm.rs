@@ -0,0 +1,450 @@
+//! generalized linear models fitted by iteratively reweighted least squares
+use crate::distribution::{ChiSquared, Normal, StudentT};
+use crate::estimate::Estimate;
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::TestResult;
+use crate::matrix::Matrix;
+use crate::regression::{
+    apply_weights, design_matrix, least_squares, linear_predictor, predict_rows, Coefficient,
+};
+
+const MAX_ITERATION: usize = 100;
+
+/// times a step is halved when the deviance is not finite or increases
+const MAX_HALVING: usize = 30;
+
+/// relative change of the deviance under which the iterations stop
+const TOLERANCE: Num = 1e-10;
+
+/// function mapping the mean of the response to the linear predictor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    Identity,
+    Log,
+    /// `ln(mu / (1 - mu))`, whose coefficients are log odds ratios
+    Logit,
+    /// the quantile function of the standard normal distribution
+    Probit,
+    /// complementary log-log, `ln(-ln(1 - mu))`, for asymmetric probabilities
+    CLogLog,
+    /// `1 / mu`
+    Inverse,
+}
+
+impl Link {
+    /// the linear predictor of a mean
+    pub fn apply(self, mu: Num) -> Num {
+        match self {
+            Link::Identity => mu,
+            Link::Log => mu.ln(),
+            Link::Logit => (mu / (1. - mu)).ln(),
+            Link::Probit => Normal::standard().quantile(mu),
+            Link::CLogLog => (-(1. - mu).ln()).ln(),
+            Link::Inverse => 1. / mu,
+        }
+    }
+
+    /// the mean of a linear predictor. probabilities are kept away from 0 and 1
+    pub fn inverse(self, eta: Num) -> Num {
+        let probability = |p: Num| p.clamp(Num::EPSILON, 1. - Num::EPSILON);
+        match self {
+            Link::Identity => eta,
+            Link::Log => eta.exp(),
+            Link::Logit => probability(1. / (1. + (-eta).exp())),
+            Link::Probit => probability(Normal::standard().cdf(eta)),
+            Link::CLogLog => probability(1. - (-eta.exp()).exp()),
+            Link::Inverse => 1. / eta,
+        }
+    }
+
+    /// derivative of the linear predictor with respect to the mean
+    fn derivative(self, mu: Num) -> Num {
+        match self {
+            Link::Identity => 1.,
+            Link::Log => 1. / mu,
+            Link::Logit => 1. / (mu * (1. - mu)),
+            Link::Probit => 1. / Normal::standard().pdf(Normal::standard().quantile(mu)),
+            Link::CLogLog => 1. / ((mu - 1.) * (1. - mu).ln()),
+            Link::Inverse => -1. / (mu * mu),
+        }
+    }
+}
+
+/// distribution of the response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    Gaussian,
+    /// proportions of successes, with the number of trials of every observation given as its weight.
+    /// binary responses are proportions of a single trial
+    Binomial,
+    /// counts
+    Poisson,
+    /// positive responses whose standard deviation grows with their mean
+    Gamma,
+}
+
+impl Family {
+    /// the link whose linear predictor is the natural parameter of the family
+    pub fn canonical_link(self) -> Link {
+        match self {
+            Family::Gaussian => Link::Identity,
+            Family::Binomial => Link::Logit,
+            Family::Poisson => Link::Log,
+            Family::Gamma => Link::Inverse,
+        }
+    }
+
+    /// variance of an observation as a function of its mean, up to the dispersion
+    fn variance(self, mu: Num) -> Num {
+        match self {
+            Family::Gaussian => 1.,
+            Family::Binomial => mu * (1. - mu),
+            Family::Poisson => mu,
+            Family::Gamma => mu * mu,
+        }
+    }
+
+    /// contribution of one observation of unit weight to the deviance
+    fn unit_deviance(self, y: Num, mu: Num) -> Num {
+        let y_ln = |a: Num, b: Num| if a == 0. { 0. } else { a * (a / b).ln() };
+        match self {
+            Family::Gaussian => (y - mu).powi(2),
+            Family::Binomial => 2. * (y_ln(y, mu) + y_ln(1. - y, 1. - mu)),
+            Family::Poisson => 2. * (y_ln(y, mu) - (y - mu)),
+            Family::Gamma => -2. * ((y / mu).ln() - (y - mu) / mu),
+        }
+    }
+
+    /// mean the iterations start from, like R's `mustart`
+    fn initial_mean(self, y: Num, weight: Num) -> Num {
+        match self {
+            Family::Binomial => (weight * y + 0.5) / (weight + 1.),
+            Family::Poisson => y + 0.1,
+            Family::Gaussian | Family::Gamma => y,
+        }
+    }
+
+    /// whether the dispersion is fixed to one instead of estimated from the data
+    fn fixed_dispersion(self) -> bool {
+        matches!(self, Family::Binomial | Family::Poisson)
+    }
+}
+
+/// result of the iteratively reweighted least squares loop
+struct Irls {
+    coefficients: Vec<Num>,
+    unscaled_covariance: Matrix,
+    linear_predictors: Vec<Num>,
+    fitted_values: Vec<Num>,
+    deviance: Num,
+    iterations: usize,
+    converged: bool,
+}
+
+fn irls(
+    design: &Matrix,
+    y: &Input,
+    family: Family,
+    link: Link,
+    offset: &Input,
+    weights: &Input,
+) -> Irls {
+    let n = y.len();
+    let deviance = |mu: &[Num]| {
+        (0..n)
+            .map(|i| weights[i] * family.unit_deviance(y[i], mu[i]))
+            .sum::<Num>()
+    };
+    let linear_predictors = |coefficients: &[Num]| {
+        (0..n)
+            .map(|i| {
+                offset[i]
+                    + design
+                        .row(i)
+                        .iter()
+                        .zip(coefficients.iter())
+                        .map(|(x, b)| x * b)
+                        .sum::<Num>()
+            })
+            .collect::<Vec<Num>>()
+    };
+    let means = |eta: &[Num]| eta.iter().map(|e| link.inverse(*e)).collect::<Vec<Num>>();
+
+    // a model with only an offset has nothing to fit
+    if design.columns() == 0 {
+        let eta = offset.to_vec();
+        let mu = means(&eta);
+        return Irls {
+            coefficients: vec![],
+            unscaled_covariance: Matrix::zeros(0, 0),
+            deviance: deviance(&mu),
+            linear_predictors: eta,
+            fitted_values: mu,
+            iterations: 0,
+            converged: true,
+        };
+    }
+
+    let mut mu = (0..n)
+        .map(|i| family.initial_mean(y[i], weights[i]))
+        .collect::<Vec<Num>>();
+    let mut eta = mu.iter().map(|m| link.apply(*m)).collect::<Vec<Num>>();
+    let mut fit = Irls {
+        coefficients: vec![],
+        unscaled_covariance: Matrix::zeros(0, 0),
+        linear_predictors: vec![],
+        fitted_values: vec![],
+        deviance: deviance(&mu),
+        iterations: 0,
+        converged: false,
+    };
+
+    while fit.iterations < MAX_ITERATION && !fit.converged {
+        let gradient = mu.iter().map(|m| link.derivative(*m)).collect::<Vec<Num>>();
+        let working_response = (0..n)
+            .map(|i| eta[i] - offset[i] + (y[i] - mu[i]) * gradient[i])
+            .collect::<Vec<Num>>();
+        let working_weights = (0..n)
+            .map(|i| weights[i] / (family.variance(mu[i]) * gradient[i].powi(2)))
+            .collect::<Vec<Num>>();
+        let (weighted_design, weighted_response) =
+            apply_weights(design, &working_response, Some(&working_weights));
+        let (mut coefficients, unscaled_covariance) =
+            least_squares(&weighted_design, &weighted_response);
+
+        eta = linear_predictors(&coefficients);
+        mu = means(&eta);
+        let mut next_deviance = deviance(&mu);
+
+        // step halving towards the previous coefficients while the step overshoots
+        let limit = fit.deviance + TOLERANCE * (fit.deviance.abs() + 0.1);
+        let overshoots = |deviance: Num| deviance.is_nan() || deviance > limit;
+        let mut halvings = 0;
+        while !fit.coefficients.is_empty() && overshoots(next_deviance) && halvings < MAX_HALVING {
+            coefficients = coefficients
+                .iter()
+                .zip(fit.coefficients.iter())
+                .map(|(b, previous)| (b + previous) / 2.)
+                .collect();
+            eta = linear_predictors(&coefficients);
+            mu = means(&eta);
+            next_deviance = deviance(&mu);
+            halvings += 1;
+        }
+
+        fit.converged =
+            (next_deviance - fit.deviance).abs() / (next_deviance.abs() + 0.1) < TOLERANCE;
+        fit.iterations += 1;
+        fit.deviance = next_deviance;
+        fit.coefficients = coefficients;
+        fit.unscaled_covariance = unscaled_covariance;
+    }
+    fit.linear_predictors = eta;
+    fit.fitted_values = mu;
+    fit
+}
+
+/// generalized linear model. coefficients start with the intercept when there is one
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralizedLinearModel {
+    pub family: Family,
+    pub link: Link,
+    pub intercept: bool,
+    pub coefficients: Vec<Num>,
+    /// estimated mean of every observation, e.g. the predicted probabilities of a binomial model
+    pub fitted_values: Vec<Num>,
+    /// `offset + X b` of every observation
+    pub linear_predictors: Vec<Num>,
+    /// twice the log likelihood ratio of the saturated model to this one, up to the dispersion
+    pub deviance: Num,
+    /// deviance of the model with only the intercept and the offset
+    pub null_deviance: Num,
+    pub df_residual: Num,
+    pub df_null: Num,
+    /// one for the binomial and poisson families, the pearson estimate otherwise
+    pub dispersion: Num,
+    pub iterations: usize,
+    /// whether the deviance settled before the iteration limit
+    pub converged: bool,
+    /// whether some fitted probabilities of a binomial model are numerically 0 or 1, which happens when
+    /// the predictors separate the outcomes. the coefficients and their standard errors then grow without bound
+    pub separation: bool,
+    unscaled_covariance: Matrix,
+}
+
+impl GeneralizedLinearModel {
+    /// linear predictor of one observation, given as one value per predictor, without offset
+    pub fn linear_predictor(&self, x: &Input) -> Num {
+        linear_predictor(&self.coefficients, self.intercept, x)
+    }
+
+    /// estimated mean of one observation, given as one value per predictor, without offset.
+    /// for a binomial model it is the predicted probability
+    pub fn predict(&self, x: &Input) -> Num {
+        self.link.inverse(self.linear_predictor(x))
+    }
+
+    /// estimated means of many observations, given as one slice per predictor, without offset
+    pub fn predict_many(&self, predictors: &[&Input]) -> Vec<Num> {
+        predict_rows(predictors, |row| self.predict(row))
+    }
+
+    /// wald test of every coefficient against zero with intervals at `level`. `t_statistic` holds a z statistic
+    /// when the dispersion is fixed, and a t statistic with `df_residual` degrees of freedom otherwise
+    pub fn wald_tests(&self, level: Num) -> Vec<Coefficient> {
+        let normal = Normal::standard();
+        let t = StudentT::new(self.df_residual);
+        let fixed = self.family.fixed_dispersion();
+        let cdf = |x: Num| if fixed { normal.cdf(x) } else { t.cdf(x) };
+        let quantile = if fixed {
+            normal.quantile(1. - (1. - level) / 2.)
+        } else {
+            t.quantile(1. - (1. - level) / 2.)
+        };
+
+        self.coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, estimate)| {
+                let standard_error = (self.dispersion * self.unscaled_covariance[(j, j)]).sqrt();
+                let statistic = estimate / standard_error;
+                Coefficient {
+                    estimate: *estimate,
+                    standard_error,
+                    t_statistic: statistic,
+                    p_value: 2. * cdf(-statistic.abs()),
+                    confidence_interval: (
+                        estimate - quantile * standard_error,
+                        estimate + quantile * standard_error,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// `exp` of every coefficient with its wald interval at `level`. these are odds ratios
+    /// under the logit link and rate ratios under the log link
+    pub fn odds_ratios(&self, level: Num) -> Vec<Estimate> {
+        self.wald_tests(level)
+            .iter()
+            .map(|c| Estimate {
+                value: c.estimate.exp(),
+                lower: c.confidence_interval.0.exp(),
+                upper: c.confidence_interval.1.exp(),
+                level,
+            })
+            .collect()
+    }
+
+    /// likelihood ratio test of the model against the null model, from the drop in deviance.
+    /// the chi-square distribution is exact only asymptotically, and only when the dispersion is fixed
+    pub fn deviance_test(&self) -> TestResult {
+        let statistic = (self.null_deviance - self.deviance) / self.dispersion;
+        let df = self.df_null - self.df_residual;
+        TestResult {
+            statistic,
+            df: Some(df),
+            p_value: ChiSquared::new(df).sf(statistic),
+        }
+    }
+}
+
+/// fits a generalized linear model of `y` on `predictors`, one slice per variable.
+/// `offset` is added to the linear predictor with a fixed coefficient of one, e.g. the log exposure of counts,
+/// and `weights` are prior weights of the observations, e.g. the number of trials of binomial proportions.
+/// without predictors nor intercept only the offset is evaluated
+pub fn glm(
+    predictors: &[&Input],
+    y: &Input,
+    family: Family,
+    link: Link,
+    offset: Option<&Input>,
+    weights: Option<&Input>,
+    intercept: bool,
+) -> GeneralizedLinearModel {
+    let n = y.len();
+    let zeros = vec![0 as Num; n];
+    let ones = vec![1 as Num; n];
+    let offset = offset.unwrap_or(&zeros);
+    let weights = weights.unwrap_or(&ones);
+
+    let design = if predictors.is_empty() && intercept {
+        Matrix::from_columns(&[&ones])
+    } else if predictors.is_empty() {
+        Matrix::zeros(n, 0)
+    } else {
+        design_matrix(predictors, intercept)
+    };
+    let fit = irls(&design, y, family, link, offset, weights);
+
+    let null_deviance = if intercept {
+        irls(
+            &Matrix::from_columns(&[&ones]),
+            y,
+            family,
+            link,
+            offset,
+            weights,
+        )
+        .deviance
+    } else {
+        (0..n)
+            .map(|i| weights[i] * family.unit_deviance(y[i], link.inverse(offset[i])))
+            .sum()
+    };
+
+    let observations = weights.iter().filter(|w| **w > 0.).count() as Num;
+    let df_residual = observations - design.columns() as Num;
+    let dispersion = if family.fixed_dispersion() {
+        1.
+    } else {
+        (0..n)
+            .map(|i| {
+                let mu = fit.fitted_values[i];
+                weights[i] * (y[i] - mu).powi(2) / family.variance(mu)
+            })
+            .sum::<Num>()
+            / df_residual
+    };
+
+    let separation = family == Family::Binomial
+        && fit
+            .fitted_values
+            .iter()
+            .any(|mu| *mu <= 10. * Num::EPSILON || *mu >= 1. - 10. * Num::EPSILON);
+
+    GeneralizedLinearModel {
+        family,
+        link,
+        intercept,
+        coefficients: fit.coefficients,
+        fitted_values: fit.fitted_values,
+        linear_predictors: fit.linear_predictors,
+        deviance: fit.deviance,
+        null_deviance,
+        df_residual,
+        df_null: if intercept {
+            observations - 1.
+        } else {
+            observations
+        },
+        dispersion,
+        iterations: fit.iterations,
+        converged: fit.converged,
+        separation,
+        unscaled_covariance: fit.unscaled_covariance,
+    }
+}
+
+/// logistic regression of binary outcomes, a binomial model with the logit link
+pub fn logistic_regression(predictors: &[&Input], y: &Input) -> GeneralizedLinearModel {
+    glm(
+        predictors,
+        y,
+        Family::Binomial,
+        Link::Logit,
+        None,
+        None,
+        true,
+    )
+}
@@ -0,0 +1,230 @@
+//! parametric hypothesis tests for means
+use crate::distribution::{Normal, StudentT};
+use crate::generic_types::{Input, Num};
+use crate::uni_dimentional::{mean, sample_variance};
+
+/// direction of the alternative hypothesis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alternative {
+    /// the true value differs from the hypothesized one
+    TwoSided,
+    /// the true value is smaller than the hypothesized one
+    Less,
+    /// the true value is greater than the hypothesized one
+    Greater,
+}
+
+impl Default for Alternative {
+    fn default() -> Self {
+        Alternative::TwoSided
+    }
+}
+
+impl Alternative {
+    /// p-value of a statistic whose null distribution is symmetric around zero.
+    /// `cdf` is the cumulative distribution function of that null distribution
+    pub(crate) fn symmetric_p_value<F>(self, statistic: Num, cdf: F) -> Num
+    where
+        F: Fn(Num) -> Num,
+    {
+        match self {
+            Alternative::TwoSided => (2. * cdf(-statistic.abs())).min(1.),
+            Alternative::Less => cdf(statistic),
+            Alternative::Greater => cdf(-statistic),
+        }
+    }
+
+    /// confidence interval of `estimate` at `level`.
+    /// `quantile` is the inverse cdf of the standardized null distribution.
+    /// one sided alternatives give a one sided interval like R's `t.test` does
+    pub(crate) fn symmetric_interval<F>(
+        self,
+        estimate: Num,
+        standard_error: Num,
+        level: Num,
+        quantile: F,
+    ) -> (Num, Num)
+    where
+        F: Fn(Num) -> Num,
+    {
+        match self {
+            Alternative::TwoSided => {
+                let margin = quantile(1. - (1. - level) / 2.) * standard_error;
+                (estimate - margin, estimate + margin)
+            }
+            Alternative::Less => (
+                Num::NEG_INFINITY,
+                estimate + quantile(level) * standard_error,
+            ),
+            Alternative::Greater => (estimate - quantile(level) * standard_error, Num::INFINITY),
+        }
+    }
+}
+
+/// result of a hypothesis test that only reports its statistic and p-value
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub statistic: Num,
+    /// degrees of freedom when the p-value comes from a chi-square or similar approximation
+    pub df: Option<Num>,
+    pub p_value: Num,
+}
+
+/// result of a test whose statistic follows an F distribution under the null hypothesis
+#[derive(Debug, Clone, PartialEq)]
+pub struct FTestResult {
+    pub statistic: Num,
+    pub df1: Num,
+    pub df2: Num,
+    pub p_value: Num,
+}
+
+/// result of a t-test or a z-test
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeanTestResult {
+    /// the t or z statistic
+    pub statistic: Num,
+    /// degrees of freedom of the null distribution. infinite for z-tests
+    pub df: Num,
+    pub p_value: Num,
+    /// the estimated mean (one sample) or the difference of means (two samples)
+    pub mean_difference: Num,
+    pub confidence_interval: (Num, Num),
+}
+
+fn t_test_from_parts(
+    estimate: Num,
+    hypothesized: Num,
+    standard_error: Num,
+    df: Num,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let distribution = StudentT::new(df);
+    let statistic = (estimate - hypothesized) / standard_error;
+
+    MeanTestResult {
+        statistic,
+        df,
+        p_value: alternative.symmetric_p_value(statistic, |x| distribution.cdf(x)),
+        mean_difference: estimate,
+        confidence_interval: alternative.symmetric_interval(estimate, standard_error, level, |p| {
+            distribution.quantile(p)
+        }),
+    }
+}
+
+/// one sample t-test of `H0: mean(input) == mu`
+pub fn one_sample_t_test(
+    input: &Input,
+    mu: Num,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let n = input.len() as Num;
+    let standard_error = (sample_variance(input) / n).sqrt();
+
+    t_test_from_parts(mean(input), mu, standard_error, n - 1., alternative, level)
+}
+
+/// student's two sample t-test assuming both populations share the same variance
+pub fn two_sample_t_test(
+    input1: &Input,
+    input2: &Input,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let n1 = input1.len() as Num;
+    let n2 = input2.len() as Num;
+    let df = n1 + n2 - 2.;
+
+    let pooled = ((n1 - 1.) * sample_variance(input1) + (n2 - 1.) * sample_variance(input2)) / df;
+    let standard_error = (pooled * (1. / n1 + 1. / n2)).sqrt();
+
+    let difference = mean(input1) - mean(input2);
+    t_test_from_parts(difference, 0., standard_error, df, alternative, level)
+}
+
+/// welch's two sample t-test. variances are not assumed to be equal
+/// and the degrees of freedom are approximated with the welch–satterthwaite equation
+pub fn welch_t_test(
+    input1: &Input,
+    input2: &Input,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let n1 = input1.len() as Num;
+    let n2 = input2.len() as Num;
+    let v1 = sample_variance(input1) / n1;
+    let v2 = sample_variance(input2) / n2;
+
+    let standard_error = (v1 + v2).sqrt();
+    let df = (v1 + v2).powi(2) / (v1 * v1 / (n1 - 1.) + v2 * v2 / (n2 - 1.));
+
+    let difference = mean(input1) - mean(input2);
+    t_test_from_parts(difference, 0., standard_error, df, alternative, level)
+}
+
+/// paired t-test. it is a one sample t-test on `input1[i] - input2[i]`
+pub fn paired_t_test(
+    input1: &Input,
+    input2: &Input,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let differences = input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(a, b)| a - b)
+        .collect::<Vec<Num>>();
+
+    one_sample_t_test(&differences, 0., alternative, level)
+}
+
+fn z_test_from_parts(
+    estimate: Num,
+    hypothesized: Num,
+    standard_error: Num,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let distribution = Normal::standard();
+    let statistic = (estimate - hypothesized) / standard_error;
+
+    MeanTestResult {
+        statistic,
+        df: Num::INFINITY,
+        p_value: alternative.symmetric_p_value(statistic, |x| distribution.cdf(x)),
+        mean_difference: estimate,
+        confidence_interval: alternative.symmetric_interval(estimate, standard_error, level, |p| {
+            distribution.quantile(p)
+        }),
+    }
+}
+
+/// one sample z-test of `H0: mean(input) == mu` with a known population standard deviation `sigma`
+pub fn one_sample_z_test(
+    input: &Input,
+    mu: Num,
+    sigma: Num,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let standard_error = sigma / (input.len() as Num).sqrt();
+    z_test_from_parts(mean(input), mu, standard_error, alternative, level)
+}
+
+/// two sample z-test with known population standard deviations `sigma1` and `sigma2`
+pub fn two_sample_z_test(
+    input1: &Input,
+    input2: &Input,
+    sigma1: Num,
+    sigma2: Num,
+    alternative: Alternative,
+    level: Num,
+) -> MeanTestResult {
+    let standard_error =
+        (sigma1 * sigma1 / input1.len() as Num + sigma2 * sigma2 / input2.len() as Num).sqrt();
+    let difference = mean(input1) - mean(input2);
+    z_test_from_parts(difference, 0., standard_error, alternative, level)
+}
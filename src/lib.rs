@@ -2,14 +2,50 @@
 
 pub mod uni_dimentional;
 pub mod bi_dimentional;
+pub mod multi_dimentional;
+pub mod regression;
+pub mod matrix;
 pub mod timeseries;
 pub mod generic_types;
+pub mod distribution;
+pub mod hypothesis;
+pub mod nonparametric;
+pub mod anova;
+pub mod categorical;
+pub mod proportion;
+pub mod multiple_testing;
+pub mod estimate;
+pub mod effect_size;
+pub mod power;
+pub mod resampling;
+pub mod robust_regression;
+pub mod errors_in_variables;
+pub mod regularized_regression;
+pub mod glm;
 mod test;
 
 pub use OrdFloat;
 pub use crate::{
     uni_dimentional::*,
     bi_dimentional::*,
+    multi_dimentional::*,
+    regression::*,
+    matrix::*,
     timeseries::*,
-    generic_types::*
+    generic_types::*,
+    distribution::*,
+    hypothesis::*,
+    nonparametric::*,
+    anova::*,
+    categorical::*,
+    proportion::*,
+    multiple_testing::*,
+    estimate::*,
+    effect_size::*,
+    power::*,
+    resampling::*,
+    robust_regression::*,
+    errors_in_variables::*,
+    regularized_regression::*,
+    glm::*
 };
\ No newline at end of file
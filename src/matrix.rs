@@ -0,0 +1,361 @@
+//! dense matrices for the functions that work on many variables at once
+use crate::generic_types::{Input, Num, OrdFloat};
+use std::ops::{Index, IndexMut};
+
+/// dense matrix of `Num` stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    columns: usize,
+    data: Vec<Num>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        Matrix {
+            rows,
+            columns,
+            data: vec![0 as Num; rows * columns],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Matrix::zeros(size, size);
+        (0..size).for_each(|i| matrix[(i, i)] = 1.);
+        matrix
+    }
+
+    /// matrix whose rows are the given slices, which must have the same length
+    pub fn from_rows(rows: &[&Input]) -> Self {
+        let columns = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == columns),
+            "every row must have the same length"
+        );
+        Matrix {
+            rows: rows.len(),
+            columns,
+            data: rows.iter().flat_map(|r| r.iter().copied()).collect(),
+        }
+    }
+
+    /// matrix whose columns are the given slices, e.g. one variable per column
+    pub fn from_columns(columns: &[&Input]) -> Self {
+        Matrix::from_rows(columns).transpose()
+    }
+
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn row(&self, i: usize) -> &[Num] {
+        &self.data[i * self.columns..(i + 1) * self.columns]
+    }
+
+    pub fn column(&self, j: usize) -> Vec<Num> {
+        (0..self.rows).map(|i| self[(i, j)]).collect()
+    }
+
+    pub fn diagonal(&self) -> Vec<Num> {
+        (0..self.rows.min(self.columns))
+            .map(|i| self[(i, i)])
+            .collect()
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut transposed = Matrix::zeros(self.columns, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.columns {
+                transposed[(j, i)] = self[(i, j)];
+            }
+        }
+        transposed
+    }
+
+    /// matrix product `self * other`
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            self.columns, other.rows,
+            "the matrices do not have matching dimensions"
+        );
+        let mut product = Matrix::zeros(self.rows, other.columns);
+        for i in 0..self.rows {
+            for k in 0..self.columns {
+                let a = self[(i, k)];
+                for j in 0..other.columns {
+                    product[(i, j)] += a * other[(k, j)];
+                }
+            }
+        }
+        product
+    }
+
+    /// inverse of a square matrix by gauss–jordan elimination with partial pivoting.
+    /// returns `None` when the matrix is singular
+    pub fn inverse(&self) -> Option<Matrix> {
+        assert_eq!(
+            self.rows, self.columns,
+            "only square matrices have an inverse"
+        );
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut inverse = Matrix::identity(n);
+        let scale = self.data.iter().fold(0 as Num, |acc, x| acc.max(x.abs()));
+
+        for column in 0..n {
+            let pivot = (column..n)
+                .max_by_key(|i| OrdFloat(a[(*i, column)].abs()))
+                .unwrap();
+            if a[(pivot, column)].abs() <= scale * Num::EPSILON * n as Num {
+                return None;
+            }
+            a.swap_rows(pivot, column);
+            inverse.swap_rows(pivot, column);
+
+            let divisor = a[(column, column)];
+            for j in 0..n {
+                a[(column, j)] /= divisor;
+                inverse[(column, j)] /= divisor;
+            }
+            for i in (0..n).filter(|i| *i != column) {
+                let factor = a[(i, column)];
+                if factor != 0. {
+                    for j in 0..n {
+                        a[(i, j)] -= factor * a[(column, j)];
+                        inverse[(i, j)] -= factor * inverse[(column, j)];
+                    }
+                }
+            }
+        }
+        Some(inverse)
+    }
+
+    /// thin householder qr decomposition `self = Q R` of a matrix with at least as many rows as columns.
+    /// `Q` has orthonormal columns and `R` is square and upper triangular
+    pub fn qr(&self) -> (Matrix, Matrix) {
+        let (m, n) = (self.rows, self.columns);
+        assert!(m >= n, "qr needs at least as many rows as columns");
+        let mut a = self.clone();
+        let mut reflectors = Vec::with_capacity(n);
+
+        for k in 0..n {
+            let norm = (k..m).map(|i| a[(i, k)].powi(2)).sum::<Num>().sqrt();
+            let alpha = if a[(k, k)] > 0. { -norm } else { norm };
+            let mut v = (k..m).map(|i| a[(i, k)]).collect::<Vec<Num>>();
+            v[0] -= alpha;
+            let length = v.iter().map(|x| x * x).sum::<Num>().sqrt();
+            if length > 0. {
+                v.iter_mut().for_each(|x| *x /= length);
+                a.reflect(&v, k);
+            }
+            reflectors.push(v);
+        }
+
+        let mut q = Matrix::zeros(m, n);
+        (0..n).for_each(|i| q[(i, i)] = 1.);
+        for (k, v) in reflectors.iter().enumerate().rev() {
+            q.reflect(v, k);
+        }
+
+        let mut r = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                r[(i, j)] = a[(i, j)];
+            }
+        }
+        (q, r)
+    }
+
+    /// applies the householder reflection `I - 2 v v'` to the rows from `offset` on
+    fn reflect(&mut self, v: &[Num], offset: usize) {
+        for j in 0..self.columns {
+            let dot = v
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x * self[(offset + i, j)])
+                .sum::<Num>();
+            v.iter()
+                .enumerate()
+                .for_each(|(i, x)| self[(offset + i, j)] -= 2. * x * dot);
+        }
+    }
+
+    /// inverse of an upper triangular matrix by back substitution.
+    /// returns `None` when a diagonal element is negligible
+    pub fn upper_triangular_inverse(&self) -> Option<Matrix> {
+        let n = self.rows;
+        let scale = self
+            .diagonal()
+            .iter()
+            .fold(0 as Num, |acc, x| acc.max(x.abs()));
+        if self
+            .diagonal()
+            .iter()
+            .any(|d| d.abs() <= scale * Num::EPSILON * n as Num)
+        {
+            return None;
+        }
+
+        let mut inverse = Matrix::zeros(n, n);
+        for j in 0..n {
+            inverse[(j, j)] = 1. / self[(j, j)];
+            for i in (0..j).rev() {
+                let sum = (i + 1..=j)
+                    .map(|k| self[(i, k)] * inverse[(k, j)])
+                    .sum::<Num>();
+                inverse[(i, j)] = -sum / self[(i, i)];
+            }
+        }
+        Some(inverse)
+    }
+
+    /// eigenvalues in descending order and the matching eigenvectors, as columns,
+    /// of a symmetric matrix by the cyclic jacobi method
+    pub fn symmetric_eigen(&self) -> (Vec<Num>, Matrix) {
+        assert_eq!(self.rows, self.columns, "eigenvalues need a square matrix");
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut vectors = Matrix::identity(n);
+
+        for _ in 0..100 {
+            let off_diagonal = (0..n)
+                .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a[(i, j)].powi(2))
+                .sum::<Num>();
+            let total = a.data.iter().map(|x| x * x).sum::<Num>();
+            if off_diagonal <= total * Num::EPSILON * Num::EPSILON {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a[(p, q)] == 0. {
+                        continue;
+                    }
+                    let theta = (a[(q, q)] - a[(p, p)]) / (2. * a[(p, q)]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                    let c = 1. / (t * t + 1.).sqrt();
+                    let s = t * c;
+                    // a = J' a J for the rotation J in the (p, q) plane
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = c * akp - s * akq;
+                        a[(k, q)] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = c * apk - s * aqk;
+                        a[(q, k)] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (vectors[(k, p)], vectors[(k, q)]);
+                        vectors[(k, p)] = c * vkp - s * vkq;
+                        vectors[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by_key(|i| std::cmp::Reverse(OrdFloat(a[(*i, *i)])));
+        let mut sorted = Matrix::zeros(n, n);
+        for (j, i) in order.iter().enumerate() {
+            for k in 0..n {
+                sorted[(k, j)] = vectors[(k, *i)];
+            }
+        }
+        (order.iter().map(|i| a[(*i, *i)]).collect(), sorted)
+    }
+
+    /// thin singular value decomposition `self = U S V'` of a matrix with at least as many rows as columns,
+    /// by the one sided jacobi method. returns `U`, the singular values in descending order and `V`
+    pub fn svd(&self) -> (Matrix, Vec<Num>, Matrix) {
+        let (m, n) = (self.rows, self.columns);
+        assert!(m >= n, "svd needs at least as many rows as columns");
+        let mut u = self.clone();
+        let mut v = Matrix::identity(n);
+
+        for _ in 0..100 {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let alpha = (0..m).map(|i| u[(i, p)].powi(2)).sum::<Num>();
+                    let beta = (0..m).map(|i| u[(i, q)].powi(2)).sum::<Num>();
+                    let gamma = (0..m).map(|i| u[(i, p)] * u[(i, q)]).sum::<Num>();
+                    if gamma.abs() <= Num::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+                    // rotation in the (p, q) plane that makes columns p and q orthogonal
+                    let zeta = (beta - alpha) / (2. * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.).sqrt());
+                    let c = 1. / (t * t + 1.).sqrt();
+                    let s = t * c;
+                    for k in 0..m {
+                        let (ukp, ukq) = (u[(k, p)], u[(k, q)]);
+                        u[(k, p)] = c * ukp - s * ukq;
+                        u[(k, q)] = s * ukp + c * ukq;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (v[(k, p)], v[(k, q)]);
+                        v[(k, p)] = c * vkp - s * vkq;
+                        v[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        // the singular values are the norms of the orthogonalized columns
+        let norms = (0..n)
+            .map(|j| (0..m).map(|i| u[(i, j)].powi(2)).sum::<Num>().sqrt())
+            .collect::<Vec<Num>>();
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by_key(|j| std::cmp::Reverse(OrdFloat(norms[*j])));
+        let (mut left, mut right) = (Matrix::zeros(m, n), Matrix::zeros(n, n));
+        for (j, from) in order.iter().enumerate() {
+            for k in 0..m {
+                left[(k, j)] = if norms[*from] > 0. {
+                    u[(k, *from)] / norms[*from]
+                } else {
+                    0.
+                };
+            }
+            for k in 0..n {
+                right[(k, j)] = v[(k, *from)];
+            }
+        }
+        (left, order.iter().map(|j| norms[*j]).collect(), right)
+    }
+
+    fn swap_rows(&mut self, i: usize, j: usize) {
+        if i != j {
+            for k in 0..self.columns {
+                self.data.swap(i * self.columns + k, j * self.columns + k);
+            }
+        }
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = Num;
+
+    #[inline]
+    fn index(&self, (i, j): (usize, usize)) -> &Num {
+        &self.data[i * self.columns + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    #[inline]
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Num {
+        &mut self.data[i * self.columns + j]
+    }
+}
@@ -0,0 +1,205 @@
+//! functions that take many variables at once, one slice per variable
+use crate::bi_dimentional::{
+    correlation, kendall_tau, sample_covariance, spearman_rho, KendallVariant,
+};
+use crate::generic_types::{Input, Num};
+use crate::matrix::Matrix;
+
+/// how observations with a NaN in some variable are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingValues {
+    /// every pair of variables uses the observations where both of them are present.
+    /// uses more data, but the matrix may not be positive semi definite
+    Pairwise,
+    /// only observations where every variable is present are used
+    Listwise,
+}
+
+/// correlation coefficients a correlation matrix can be built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationMethod {
+    Pearson,
+    Spearman,
+    /// kendall's tau-b
+    Kendall,
+}
+
+/// the observations of `columns` where every variable is present
+fn complete_cases(columns: &[&Input]) -> Vec<Vec<Num>> {
+    let n = columns.first().map_or(0, |c| c.len());
+    assert!(
+        columns.iter().all(|c| c.len() == n),
+        "every variable must have the same number of observations"
+    );
+    let complete = (0..n)
+        .filter(|i| columns.iter().all(|c| !c[*i].is_nan()))
+        .collect::<Vec<usize>>();
+    columns
+        .iter()
+        .map(|c| complete.iter().map(|i| c[*i]).collect())
+        .collect()
+}
+
+/// the observations of two variables where both are present
+fn complete_pairs(input1: &Input, input2: &Input) -> (Vec<Num>, Vec<Num>) {
+    input1
+        .iter()
+        .zip(input2.iter())
+        .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+        .map(|(x, y)| (*x, *y))
+        .unzip()
+}
+
+/// symmetric matrix of `measure` for every pair of variables
+fn pairwise_matrix<F>(columns: &[&Input], missing: MissingValues, measure: F) -> Matrix
+where
+    F: Fn(&Input, &Input) -> Num,
+{
+    let p = columns.len();
+    let complete = match missing {
+        MissingValues::Listwise => complete_cases(columns),
+        MissingValues::Pairwise => columns.iter().map(|c| c.to_vec()).collect(),
+    };
+
+    let mut matrix = Matrix::zeros(p, p);
+    for i in 0..p {
+        for j in i..p {
+            let value = match missing {
+                MissingValues::Listwise => measure(&complete[i], &complete[j]),
+                MissingValues::Pairwise => {
+                    let (x, y) = complete_pairs(&complete[i], &complete[j]);
+                    measure(&x, &y)
+                }
+            };
+            matrix[(i, j)] = value;
+            matrix[(j, i)] = value;
+        }
+    }
+    matrix
+}
+
+/// sample covariance matrix of the variables in `columns`
+pub fn covariance_matrix(columns: &[&Input], missing: MissingValues) -> Matrix {
+    pairwise_matrix(columns, missing, sample_covariance)
+}
+
+/// correlation matrix of the variables in `columns`
+pub fn correlation_matrix(
+    columns: &[&Input],
+    method: CorrelationMethod,
+    missing: MissingValues,
+) -> Matrix {
+    let mut matrix = match method {
+        CorrelationMethod::Pearson => pairwise_matrix(columns, missing, correlation),
+        CorrelationMethod::Spearman => pairwise_matrix(columns, missing, spearman_rho),
+        CorrelationMethod::Kendall => pairwise_matrix(columns, missing, |x, y| {
+            kendall_tau(x, y, KendallVariant::B)
+        }),
+    };
+    (0..columns.len()).for_each(|i| matrix[(i, i)] = 1.);
+    matrix
+}
+
+/// inverse of the correlation matrix. panics when it is singular
+fn precision_matrix(
+    columns: &[&Input],
+    method: CorrelationMethod,
+    missing: MissingValues,
+) -> Matrix {
+    correlation_matrix(columns, method, missing)
+        .inverse()
+        .expect("the correlation matrix is singular")
+}
+
+/// partial correlation of every pair of variables given all the other ones,
+/// `-P_ij / sqrt(P_ii P_jj)` for the precision matrix `P`
+pub fn partial_correlation_matrix(
+    columns: &[&Input],
+    method: CorrelationMethod,
+    missing: MissingValues,
+) -> Matrix {
+    let precision = precision_matrix(columns, method, missing);
+    let p = columns.len();
+    let mut partial = Matrix::identity(p);
+    for i in 0..p {
+        for j in (0..p).filter(|j| *j != i) {
+            partial[(i, j)] = -precision[(i, j)] / (precision[(i, i)] * precision[(j, j)]).sqrt();
+        }
+    }
+    partial
+}
+
+/// semi-partial (part) correlations. entry `(i, j)` correlates variable `i` with the part of
+/// variable `j` that the variables other than `i` and `j` do not explain, so the matrix is not symmetric
+pub fn semi_partial_correlation_matrix(
+    columns: &[&Input],
+    method: CorrelationMethod,
+    missing: MissingValues,
+) -> Matrix {
+    let precision = precision_matrix(columns, method, missing);
+    let p = columns.len();
+    let mut semi_partial = Matrix::identity(p);
+    for i in 0..p {
+        for j in (0..p).filter(|j| *j != i) {
+            let (pii, pjj, pij) = (precision[(i, i)], precision[(j, j)], precision[(i, j)]);
+            semi_partial[(i, j)] = -pij / (pii.sqrt() * (pii * pjj - pij * pij).sqrt());
+        }
+    }
+    semi_partial
+}
+
+/// ledoit–wolf shrinkage of the covariance matrix towards a multiple of the identity,
+/// well conditioned even with more variables than observations.
+/// observations with a NaN are dropped listwise.
+/// returns the shrunk covariance matrix, which divides by `n`, and the shrinkage intensity in `[0, 1]`
+pub fn ledoit_wolf(columns: &[&Input]) -> (Matrix, Num) {
+    let data = complete_cases(columns);
+    let p = data.len();
+    let n = data.first().map_or(0, |c| c.len());
+    let centered = data
+        .iter()
+        .map(|c| {
+            let mean = c.iter().sum::<Num>() / n as Num;
+            c.iter().map(|x| x - mean).collect::<Vec<Num>>()
+        })
+        .collect::<Vec<Vec<Num>>>();
+
+    let mut sample = Matrix::zeros(p, p);
+    for i in 0..p {
+        for j in i..p {
+            let value = (0..n).map(|k| centered[i][k] * centered[j][k]).sum::<Num>() / n as Num;
+            sample[(i, j)] = value;
+            sample[(j, i)] = value;
+        }
+    }
+
+    let target = sample.diagonal().iter().sum::<Num>() / p as Num;
+    // squared frobenius distance of the sample covariance from the target
+    let mut dispersion = 0 as Num;
+    // sum over observations of the squared frobenius distance of `x x'` from the sample covariance
+    let mut variability = 0 as Num;
+    for i in 0..p {
+        for j in 0..p {
+            let identity = if i == j { target } else { 0. };
+            dispersion += (sample[(i, j)] - identity).powi(2);
+            variability += (0..n)
+                .map(|k| (centered[i][k] * centered[j][k] - sample[(i, j)]).powi(2))
+                .sum::<Num>();
+        }
+    }
+    let variability = (variability / (n * n) as Num).min(dispersion);
+    let shrinkage = if dispersion > 0. {
+        variability / dispersion
+    } else {
+        0.
+    };
+
+    let mut shrunk = sample;
+    for i in 0..p {
+        for j in 0..p {
+            let identity = if i == j { target } else { 0. };
+            shrunk[(i, j)] = shrinkage * identity + (1. - shrinkage) * shrunk[(i, j)];
+        }
+    }
+    (shrunk, shrinkage)
+}
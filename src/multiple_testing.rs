@@ -0,0 +1,137 @@
+//! adjustment of p-values for multiple comparisons
+use crate::generic_types::{Input, Num};
+use crate::uni_dimentional::argsort;
+
+/// p-value adjustment methods.
+/// the first four control the family wise error rate and the last two the false discovery rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Bonferroni,
+    Holm,
+    Hochberg,
+    Hommel,
+    /// benjamini–hochberg
+    BenjaminiHochberg,
+    /// benjamini–yekutieli, valid under any dependency between the tests
+    BenjaminiYekutieli,
+}
+
+/// scatters `sorted[k]`, which belongs to `p_values[order[k]]`, back to the original order
+fn restore_order(sorted: &[Num], order: &[usize]) -> Vec<Num> {
+    let mut adjusted = vec![0 as Num; sorted.len()];
+    order
+        .iter()
+        .zip(sorted.iter())
+        .for_each(|(i, p)| adjusted[*i] = *p);
+    adjusted
+}
+
+/// step-up adjustment `min_{j >= i} factor(j) * p_(j)` on the ascending p-values
+fn step_up<F>(sorted: &[Num], factor: F) -> Vec<Num>
+where
+    F: Fn(usize) -> Num,
+{
+    let mut adjusted = vec![0 as Num; sorted.len()];
+    let mut running = 1 as Num;
+    for i in (0..sorted.len()).rev() {
+        running = running.min(factor(i) * sorted[i]);
+        adjusted[i] = running;
+    }
+    adjusted
+}
+
+/// adjusts the p-values for multiple comparisons.
+/// the adjusted p-values are returned in the order of the input
+pub fn adjust_p_values(p_values: &Input, method: Method) -> Vec<Num> {
+    let n = p_values.len();
+    let m = n as Num;
+    let order = argsort(p_values);
+    let sorted = order.iter().map(|i| p_values[*i]).collect::<Vec<Num>>();
+
+    let adjusted = match method {
+        Method::Bonferroni => sorted.iter().map(|p| (p * m).min(1.)).collect(),
+        Method::Holm => {
+            // step-down: max_{j <= i} (n - j) * p_(j)
+            let mut running = 0 as Num;
+            sorted
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    running = running.max((m - i as Num) * p);
+                    running.min(1.)
+                })
+                .collect()
+        }
+        Method::Hochberg => step_up(&sorted, |i| m - i as Num),
+        Method::Hommel => hommel(&sorted),
+        Method::BenjaminiHochberg => step_up(&sorted, |i| m / (i + 1) as Num),
+        Method::BenjaminiYekutieli => {
+            let harmonic = (1..=n).map(|i| 1. / i as Num).sum::<Num>();
+            step_up(&sorted, |i| harmonic * m / (i + 1) as Num)
+                .iter()
+                .map(|p| p.min(1.))
+                .collect()
+        }
+    };
+
+    restore_order(&adjusted, &order)
+}
+
+/// hommel's adjustment of ascending p-values, following the algorithm of R's `p.adjust`
+fn hommel(sorted: &[Num]) -> Vec<Num> {
+    let n = sorted.len();
+    let m = n as Num;
+    if n < 2 {
+        return sorted.to_vec();
+    }
+
+    let initial = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, p)| m * p / (i + 1) as Num)
+        .fold(Num::INFINITY, Num::min);
+    let mut q = vec![initial; n];
+    let mut adjusted = vec![initial; n];
+
+    for size in (2..n).rev() {
+        let f = size as Num;
+        // the last size - 1 p-values against the simes critical values
+        let q1 = (n - size + 1..n)
+            .enumerate()
+            .map(|(k, i)| f * sorted[i] / (k + 2) as Num)
+            .fold(Num::INFINITY, Num::min);
+        for i in 0..=n - size {
+            q[i] = (f * sorted[i]).min(q1);
+        }
+        let tail = q[n - size];
+        q.iter_mut().skip(n - size + 1).for_each(|v| *v = tail);
+        adjusted
+            .iter_mut()
+            .zip(q.iter())
+            .for_each(|(a, v)| *a = a.max(*v));
+    }
+
+    adjusted
+        .iter()
+        .zip(sorted.iter())
+        .map(|(a, p)| a.max(*p))
+        .collect()
+}
+
+/// storey's estimate of the proportion of true null hypotheses,
+/// `#{p > lambda} / (n (1 - lambda))` capped at 1
+pub fn storey_pi0(p_values: &Input, lambda: Num) -> Num {
+    let above = p_values.iter().filter(|p| **p > lambda).count() as Num;
+    (above / (p_values.len() as Num * (1. - lambda))).min(1.)
+}
+
+/// storey's q-values, the benjamini–hochberg adjustment scaled by the estimated
+/// proportion of true null hypotheses `storey_pi0(p_values, lambda)`.
+/// `lambda` of 0.5 is a common choice
+pub fn q_values(p_values: &Input, lambda: Num) -> Vec<Num> {
+    let pi0 = storey_pi0(p_values, lambda);
+    adjust_p_values(p_values, Method::BenjaminiHochberg)
+        .iter()
+        .map(|q| q * pi0)
+        .collect()
+}
@@ -0,0 +1,278 @@
+//! rank based hypothesis tests that do not assume normally distributed data
+use crate::distribution::{Binomial, ChiSquared, Normal};
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::{Alternative, TestResult};
+use crate::uni_dimentional::{frequency_distribution, median, rank, sort_float, sum, TieMethod};
+
+/// exact distributions are used below this sample size when there are no ties
+const EXACT_LIMIT: usize = 50;
+
+/// `sum(t^3 - t)` over the sizes `t` of each group of tied values
+pub(crate) fn tie_term(input: &Input) -> Num {
+    frequency_distribution(input)
+        .values()
+        .map(|t| t.powi(3) - t)
+        .sum()
+}
+
+/// p-value of an integer valued statistic from its exact null distribution
+fn exact_p_value(distribution: &[Num], statistic: usize, alternative: Alternative) -> Num {
+    let lower = sum(&distribution[..=statistic]);
+    let upper = sum(&distribution[statistic..]);
+    match alternative {
+        Alternative::TwoSided => (2. * lower.min(upper)).min(1.),
+        Alternative::Less => lower,
+        Alternative::Greater => upper,
+    }
+}
+
+/// p-value of `statistic - expected` with standard deviation `sigma`, using the normal approximation with continuity correction
+fn normal_p_value(difference: Num, sigma: Num, alternative: Alternative) -> Num {
+    let correction = match alternative {
+        Alternative::TwoSided if difference == 0. => 0.,
+        Alternative::TwoSided => 0.5 * difference.signum(),
+        Alternative::Greater => 0.5,
+        Alternative::Less => -0.5,
+    };
+    let z = (difference - correction) / sigma;
+    let normal = Normal::standard();
+    alternative.symmetric_p_value(z, |x| normal.cdf(x))
+}
+
+/// null distribution of the mann–whitney U statistic for sample sizes `m` and `n`.
+/// the counts are the coefficients of the gaussian binomial coefficient `[m + n, m]_q`
+fn mann_whitney_distribution(m: usize, n: usize) -> Vec<Num> {
+    let max = m * n;
+    let mut coefficients = vec![0 as Num; max + 1];
+    coefficients[0] = 1.;
+
+    for i in 1..=m {
+        // multiply by (1 - q^(n + i)) and divide by (1 - q^i)
+        let a = n + i;
+        for j in (a..=max).rev() {
+            coefficients[j] -= coefficients[j - a];
+        }
+        for j in i..=max {
+            coefficients[j] += coefficients[j - i];
+        }
+    }
+
+    let total = sum(&coefficients);
+    coefficients.iter().map(|c| c / total).collect()
+}
+
+/// null distribution of the wilcoxon signed-rank statistic for `n` non-zero differences
+fn signed_rank_distribution(n: usize) -> Vec<Num> {
+    let max = n * (n + 1) / 2;
+    let mut counts = vec![0 as Num; max + 1];
+    counts[0] = 1.;
+
+    for k in 1..=n {
+        for j in (k..=max).rev() {
+            counts[j] += counts[j - k];
+        }
+    }
+
+    let total = (2 as Num).powi(n as i32);
+    counts.iter().map(|c| c / total).collect()
+}
+
+/// mann–whitney U test (wilcoxon rank-sum test) of whether `input1` tends to be larger than `input2`.
+/// the statistic is the U of `input1`. small samples without ties get an exact p-value
+pub fn mann_whitney_u(input1: &Input, input2: &Input, alternative: Alternative) -> TestResult {
+    let n1 = input1.len();
+    let n2 = input2.len();
+
+    let pooled = input1
+        .iter()
+        .chain(input2.iter())
+        .copied()
+        .collect::<Vec<Num>>();
+    let ranks = rank(&pooled, TieMethod::Average);
+    let rank_sum = sum(&ranks[..n1]);
+    let u = rank_sum - (n1 * (n1 + 1)) as Num / 2.;
+
+    let ties = tie_term(&pooled);
+    let p_value = if n1 < EXACT_LIMIT && n2 < EXACT_LIMIT && ties == 0. {
+        let distribution = mann_whitney_distribution(n1, n2);
+        exact_p_value(&distribution, u as usize, alternative)
+    } else {
+        let (f1, f2) = (n1 as Num, n2 as Num);
+        let total = f1 + f2;
+        let sigma = (f1 * f2 / 12. * ((total + 1.) - ties / (total * (total - 1.)))).sqrt();
+        normal_p_value(u - f1 * f2 / 2., sigma, alternative)
+    };
+
+    TestResult {
+        statistic: u,
+        df: None,
+        p_value,
+    }
+}
+
+/// wilcoxon signed-rank test of whether the input is symmetric around `mu`.
+/// differences equal to zero are dropped. small samples without ties get an exact p-value
+pub fn wilcoxon_signed_rank(input: &Input, mu: Num, alternative: Alternative) -> TestResult {
+    let differences = input
+        .iter()
+        .map(|x| x - mu)
+        .filter(|d| *d != 0.)
+        .collect::<Vec<Num>>();
+    let n = differences.len();
+
+    let absolute = differences.iter().map(|d| d.abs()).collect::<Vec<Num>>();
+    let ranks = rank(&absolute, TieMethod::Average);
+    let v = differences
+        .iter()
+        .zip(ranks.iter())
+        .filter(|(d, _)| **d > 0.)
+        .fold(0 as Num, |acc, (_, r)| acc + r);
+
+    let ties = tie_term(&absolute);
+    let has_zeros = n != input.len();
+    let p_value = if n < EXACT_LIMIT && ties == 0. && !has_zeros {
+        let distribution = signed_rank_distribution(n);
+        exact_p_value(&distribution, v as usize, alternative)
+    } else {
+        let f = n as Num;
+        let sigma = (f * (f + 1.) * (2. * f + 1.) / 24. - ties / 48.).sqrt();
+        normal_p_value(v - f * (f + 1.) / 4., sigma, alternative)
+    };
+
+    TestResult {
+        statistic: v,
+        df: None,
+        p_value,
+    }
+}
+
+/// wilcoxon signed-rank test on the paired differences `input1[i] - input2[i]`
+pub fn wilcoxon_signed_rank_paired(
+    input1: &Input,
+    input2: &Input,
+    alternative: Alternative,
+) -> TestResult {
+    let differences = input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(a, b)| a - b)
+        .collect::<Vec<Num>>();
+    wilcoxon_signed_rank(&differences, 0., alternative)
+}
+
+/// sign test of whether the median of the input equals `mu`.
+/// the statistic is the number of values above `mu` and the p-value is exact
+pub fn sign_test(input: &Input, mu: Num, alternative: Alternative) -> TestResult {
+    let above = input.iter().filter(|x| **x > mu).count() as u64;
+    let below = input.iter().filter(|x| **x < mu).count() as u64;
+    let binomial = Binomial::new(above + below, 0.5);
+
+    let lower = binomial.cdf(above);
+    let upper = if above == 0 {
+        1.
+    } else {
+        binomial.sf(above - 1)
+    };
+    let p_value = match alternative {
+        Alternative::TwoSided => (2. * lower.min(upper)).min(1.),
+        Alternative::Less => lower,
+        Alternative::Greater => upper,
+    };
+
+    TestResult {
+        statistic: above as Num,
+        df: None,
+        p_value,
+    }
+}
+
+/// kruskal–wallis H test of whether all groups come from the same distribution
+pub fn kruskal_wallis(groups: &[&Input]) -> TestResult {
+    let pooled = groups
+        .iter()
+        .flat_map(|g| g.iter().copied())
+        .collect::<Vec<Num>>();
+    let ranks = rank(&pooled, TieMethod::Average);
+    let total = pooled.len() as Num;
+
+    let mut offset = 0;
+    let mut h = 0 as Num;
+    for group in groups.iter() {
+        let rank_sum = sum(&ranks[offset..offset + group.len()]);
+        h += rank_sum * rank_sum / group.len() as Num;
+        offset += group.len();
+    }
+    h = 12. / (total * (total + 1.)) * h - 3. * (total + 1.);
+    h /= 1. - tie_term(&pooled) / (total.powi(3) - total);
+
+    let df = (groups.len() - 1) as Num;
+    TestResult {
+        statistic: h,
+        df: Some(df),
+        p_value: ChiSquared::new(df).sf(h),
+    }
+}
+
+/// friedman test for a randomized complete block design.
+/// each element of `blocks` holds the measurements of every treatment for one block (subject)
+pub fn friedman(blocks: &[&Input]) -> TestResult {
+    let n = blocks.len() as Num;
+    let k = blocks[0].len();
+
+    let mut rank_sums = vec![0 as Num; k];
+    let mut ties = 0 as Num;
+    for block in blocks.iter() {
+        rank(block, TieMethod::Average)
+            .iter()
+            .zip(rank_sums.iter_mut())
+            .for_each(|(r, s)| *s += r);
+        ties += tie_term(block);
+    }
+
+    let k = k as Num;
+    let expected = n * (k + 1.) / 2.;
+    let squares = rank_sums
+        .iter()
+        .fold(0 as Num, |acc, r| acc + (r - expected).powi(2));
+    let statistic = 12. * squares / (n * k * (k + 1.) - ties / (k - 1.));
+
+    let df = k - 1.;
+    TestResult {
+        statistic,
+        df: Some(df),
+        p_value: ChiSquared::new(df).sf(statistic),
+    }
+}
+
+/// mood's median test of whether all groups share the same median.
+/// values are split at the grand median and compared with a chi-square test
+pub fn mood_median_test(groups: &[&Input]) -> TestResult {
+    let pooled = groups
+        .iter()
+        .flat_map(|g| g.iter().copied())
+        .collect::<Vec<Num>>();
+    let grand_median = median(&sort_float(&pooled));
+
+    let above = groups
+        .iter()
+        .map(|g| g.iter().filter(|x| **x > grand_median).count() as Num)
+        .collect::<Vec<Num>>();
+    let total = pooled.len() as Num;
+    let total_above = sum(&above);
+
+    let mut statistic = 0 as Num;
+    for (group, above) in groups.iter().zip(above.iter()) {
+        let size = group.len() as Num;
+        let expected_above = size * total_above / total;
+        let expected_below = size - expected_above;
+        statistic += (above - expected_above).powi(2) / expected_above;
+        statistic += ((size - above) - expected_below).powi(2) / expected_below;
+    }
+
+    let df = (groups.len() - 1) as Num;
+    TestResult {
+        statistic,
+        df: Some(df),
+        p_value: ChiSquared::new(df).sf(statistic),
+    }
+}
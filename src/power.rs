@@ -0,0 +1,195 @@
+//! statistical power analysis and sample size calculation
+use crate::distribution::special::invert_monotone;
+use crate::distribution::{
+    ChiSquared, FisherF, NoncentralChiSquared, NoncentralF, NoncentralT, Normal, StudentT,
+};
+use crate::generic_types::Num;
+use crate::hypothesis::Alternative;
+
+/// design of a t-test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TTestKind {
+    OneSample,
+    /// two independent groups of `n` observations each
+    TwoSample,
+    /// `n` pairs
+    Paired,
+}
+
+/// tests a power analysis can be done for, along with the effect size measure each one expects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerTest {
+    /// effect size is cohen's d
+    T {
+        kind: TTestKind,
+        alternative: Alternative,
+    },
+    /// effect size is cohen's h, `2 asin(sqrt(p1)) - 2 asin(sqrt(p2))`.
+    /// `two_sample` compares two groups of `n` observations each
+    Proportion {
+        two_sample: bool,
+        alternative: Alternative,
+    },
+    /// one-way anova of `groups` groups of `n` observations each. effect size is cohen's f
+    Anova { groups: usize },
+    /// test of a pearson correlation. effect size is the correlation itself
+    Correlation { alternative: Alternative },
+    /// chi-square test with `df` degrees of freedom. effect size is cohen's w
+    ChiSquare { df: usize },
+}
+
+/// power analysis where exactly one of the four quantities is `None`.
+/// `solve` computes the missing one from the other three.
+/// `n` is the sample size per group and is not rounded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerAnalysis {
+    pub test: PowerTest,
+    pub effect_size: Option<Num>,
+    pub n: Option<Num>,
+    pub alpha: Option<Num>,
+    pub power: Option<Num>,
+}
+
+/// cohen's h of two proportions
+pub fn cohens_h(p1: Num, p2: Num) -> Num {
+    2. * p1.sqrt().asin() - 2. * p2.sqrt().asin()
+}
+
+/// power of a test statistic that is normal with mean `shift` under the alternative
+fn normal_power(shift: Num, alpha: Num, alternative: Alternative) -> Num {
+    let normal = Normal::standard();
+    match alternative {
+        Alternative::TwoSided => {
+            let critical = normal.quantile(1. - alpha / 2.);
+            normal.sf(critical - shift) + normal.cdf(-critical - shift)
+        }
+        Alternative::Greater => normal.sf(normal.quantile(1. - alpha) - shift),
+        Alternative::Less => normal.cdf(-normal.quantile(1. - alpha) - shift),
+    }
+}
+
+/// power of `test` for the given effect size, sample size and significance level
+pub fn power(test: PowerTest, effect_size: Num, n: Num, alpha: Num) -> Num {
+    match test {
+        PowerTest::T { kind, alternative } => {
+            let (df, ncp) = match kind {
+                TTestKind::OneSample | TTestKind::Paired => (n - 1., effect_size * n.sqrt()),
+                TTestKind::TwoSample => (2. * n - 2., effect_size * (n / 2.).sqrt()),
+            };
+            let central = StudentT::new(df);
+            let distribution = NoncentralT::new(df, ncp);
+            match alternative {
+                Alternative::TwoSided => {
+                    let critical = central.quantile(1. - alpha / 2.);
+                    distribution.sf(critical) + distribution.cdf(-critical)
+                }
+                Alternative::Greater => distribution.sf(central.quantile(1. - alpha)),
+                Alternative::Less => distribution.cdf(-central.quantile(1. - alpha)),
+            }
+        }
+        PowerTest::Proportion {
+            two_sample,
+            alternative,
+        } => {
+            let scale = if two_sample { n / 2. } else { n };
+            normal_power(effect_size * scale.sqrt(), alpha, alternative)
+        }
+        PowerTest::Anova { groups } => {
+            let k = groups as Num;
+            let (df1, df2) = (k - 1., k * (n - 1.));
+            let critical = FisherF::new(df1, df2).quantile(1. - alpha);
+            NoncentralF::new(df1, df2, k * n * effect_size * effect_size).sf(critical)
+        }
+        PowerTest::Correlation { alternative } => {
+            normal_power(effect_size.atanh() * (n - 3.).sqrt(), alpha, alternative)
+        }
+        PowerTest::ChiSquare { df } => {
+            let df = df as Num;
+            let critical = ChiSquared::new(df).quantile(1. - alpha);
+            NoncentralChiSquared::new(df, n * effect_size * effect_size).sf(critical)
+        }
+    }
+}
+
+impl PowerAnalysis {
+    /// smallest sample size for which the test is defined
+    fn minimum_n(&self) -> Num {
+        match self.test {
+            PowerTest::Correlation { .. } => 4.,
+            _ => 2.,
+        }
+    }
+
+    /// fills in the quantity that is `None`.
+    /// panics unless exactly one of them is missing
+    pub fn solve(&self) -> PowerAnalysis {
+        let missing = [
+            self.effect_size.is_none(),
+            self.n.is_none(),
+            self.alpha.is_none(),
+            self.power.is_none(),
+        ];
+        assert_eq!(
+            missing.iter().filter(|m| **m).count(),
+            1,
+            "exactly one of effect_size, n, alpha and power must be None"
+        );
+
+        let test = self.test;
+        let mut solved = *self;
+        match (self.effect_size, self.n, self.alpha, self.power) {
+            (Some(effect_size), Some(n), Some(alpha), None) => {
+                solved.power = Some(power(test, effect_size, n, alpha));
+            }
+            (Some(effect_size), None, Some(alpha), Some(target)) => {
+                let minimum = self.minimum_n();
+                let n = invert_monotone(
+                    |n| power(test, effect_size, n.max(minimum), alpha),
+                    target,
+                    minimum,
+                    minimum + 10.,
+                );
+                solved.n = Some(n.max(minimum));
+            }
+            (Some(effect_size), Some(n), None, Some(target)) => {
+                let alpha = invert_monotone(
+                    |a| power(test, effect_size, n, a.clamp(1e-12, 1. - 1e-12)),
+                    target,
+                    1e-12,
+                    1. - 1e-12,
+                );
+                solved.alpha = Some(alpha.clamp(1e-12, 1. - 1e-12));
+            }
+            (None, Some(n), Some(alpha), Some(target)) => {
+                // the power grows with the size of the effect in the direction of the alternative
+                let sign = match test {
+                    PowerTest::T {
+                        alternative: Alternative::Less,
+                        ..
+                    }
+                    | PowerTest::Proportion {
+                        alternative: Alternative::Less,
+                        ..
+                    }
+                    | PowerTest::Correlation {
+                        alternative: Alternative::Less,
+                    } => -1.,
+                    _ => 1.,
+                };
+                let upper = match test {
+                    PowerTest::Correlation { .. } => 1. - 1e-12,
+                    _ => Num::INFINITY,
+                };
+                let effect = invert_monotone(
+                    |e| power(test, sign * e.clamp(0., upper), n, alpha),
+                    target,
+                    0.,
+                    upper.min(1.),
+                );
+                solved.effect_size = Some(sign * effect.clamp(0., upper));
+            }
+            _ => unreachable!(),
+        }
+        solved
+    }
+}
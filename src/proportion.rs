@@ -0,0 +1,238 @@
+//! inference on proportions: z-tests, the exact binomial test and binomial confidence intervals
+use crate::distribution::{Beta, Binomial, Normal};
+use crate::generic_types::Num;
+use crate::hypothesis::Alternative;
+
+/// probabilities closer than this ratio are treated as equal by the exact binomial test
+const RELATIVE_ERROR: Num = 1e-7;
+
+/// result of a test on one or two proportions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProportionTestResult {
+    /// the z statistic, or the number of successes for the exact binomial test
+    pub statistic: Num,
+    pub p_value: Num,
+    /// the observed proportion (one sample) or the difference of proportions (two samples)
+    pub estimate: Num,
+    pub confidence_interval: (Num, Num),
+}
+
+/// methods to compute a confidence interval of a binomial proportion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinomialInterval {
+    /// normal approximation `p ± z sqrt(p (1 - p) / n)`. poor for small n or extreme p
+    Wald,
+    /// inversion of the score test
+    Wilson,
+    /// wald interval after adding `z^2 / 2` successes and failures
+    AgrestiCoull,
+    /// exact interval from the beta distribution, conservative
+    ClopperPearson,
+    /// equal tailed interval of the posterior under the jeffreys prior `Beta(1/2, 1/2)`
+    Jeffreys,
+}
+
+/// two sided confidence interval of the proportion `successes / trials`
+pub fn binomial_confidence_interval(
+    successes: u64,
+    trials: u64,
+    level: Num,
+    method: BinomialInterval,
+) -> (Num, Num) {
+    let (x, n) = (successes as Num, trials as Num);
+    let p = x / n;
+    let alpha = 1. - level;
+    let z = Normal::standard().quantile(1. - alpha / 2.);
+
+    match method {
+        BinomialInterval::Wald => {
+            let margin = z * (p * (1. - p) / n).sqrt();
+            ((p - margin).max(0.), (p + margin).min(1.))
+        }
+        BinomialInterval::Wilson => wilson(p, n, z),
+        BinomialInterval::AgrestiCoull => {
+            let adjusted_n = n + z * z;
+            let adjusted_p = (x + z * z / 2.) / adjusted_n;
+            let margin = z * (adjusted_p * (1. - adjusted_p) / adjusted_n).sqrt();
+            ((adjusted_p - margin).max(0.), (adjusted_p + margin).min(1.))
+        }
+        BinomialInterval::ClopperPearson => {
+            let lower = if successes == 0 {
+                0.
+            } else {
+                Beta::new(x, n - x + 1.).quantile(alpha / 2.)
+            };
+            let upper = if successes == trials {
+                1.
+            } else {
+                Beta::new(x + 1., n - x).quantile(1. - alpha / 2.)
+            };
+            (lower, upper)
+        }
+        BinomialInterval::Jeffreys => {
+            let posterior = Beta::new(x + 0.5, n - x + 0.5);
+            let lower = if successes == 0 {
+                0.
+            } else {
+                posterior.quantile(alpha / 2.)
+            };
+            let upper = if successes == trials {
+                1.
+            } else {
+                posterior.quantile(1. - alpha / 2.)
+            };
+            (lower, upper)
+        }
+    }
+}
+
+fn wilson(p: Num, n: Num, z: Num) -> (Num, Num) {
+    let z2 = z * z;
+    let center = (p + z2 / (2. * n)) / (1. + z2 / n);
+    let margin = z / (1. + z2 / n) * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+    ((center - margin).max(0.), (center + margin).min(1.))
+}
+
+/// newcombe's hybrid score interval of the difference `successes1 / trials1 - successes2 / trials2`,
+/// built from the wilson intervals of both proportions
+pub fn newcombe_interval(
+    successes1: u64,
+    trials1: u64,
+    successes2: u64,
+    trials2: u64,
+    level: Num,
+) -> (Num, Num) {
+    let z = Normal::standard().quantile(1. - (1. - level) / 2.);
+    let (n1, n2) = (trials1 as Num, trials2 as Num);
+    let p1 = successes1 as Num / n1;
+    let p2 = successes2 as Num / n2;
+    let (l1, u1) = wilson(p1, n1, z);
+    let (l2, u2) = wilson(p2, n2, z);
+
+    let difference = p1 - p2;
+    let lower = ((p1 - l1).powi(2) + (u2 - p2).powi(2)).sqrt();
+    let upper = ((u1 - p1).powi(2) + (p2 - l2).powi(2)).sqrt();
+    (difference - lower, difference + upper)
+}
+
+/// one proportion z-test of `H0: successes / trials == p0`.
+/// the confidence interval is the wilson score interval, which matches the test
+pub fn one_proportion_z_test(
+    successes: u64,
+    trials: u64,
+    p0: Num,
+    alternative: Alternative,
+    level: Num,
+) -> ProportionTestResult {
+    let n = trials as Num;
+    let p = successes as Num / n;
+    let statistic = (p - p0) / (p0 * (1. - p0) / n).sqrt();
+
+    let normal = Normal::standard();
+    let confidence_interval = match alternative {
+        Alternative::TwoSided => wilson(p, n, normal.quantile(1. - (1. - level) / 2.)),
+        Alternative::Less => (0., wilson(p, n, normal.quantile(level)).1),
+        Alternative::Greater => (wilson(p, n, normal.quantile(level)).0, 1.),
+    };
+
+    ProportionTestResult {
+        statistic,
+        p_value: alternative.symmetric_p_value(statistic, |x| normal.cdf(x)),
+        estimate: p,
+        confidence_interval,
+    }
+}
+
+/// two proportion z-test of `H0: p1 == p2`. the statistic uses the pooled proportion
+/// and the confidence interval of the difference is the unpooled wald interval
+pub fn two_proportion_z_test(
+    successes1: u64,
+    trials1: u64,
+    successes2: u64,
+    trials2: u64,
+    alternative: Alternative,
+    level: Num,
+) -> ProportionTestResult {
+    let (n1, n2) = (trials1 as Num, trials2 as Num);
+    let p1 = successes1 as Num / n1;
+    let p2 = successes2 as Num / n2;
+    let pooled = (successes1 + successes2) as Num / (n1 + n2);
+
+    let difference = p1 - p2;
+    let statistic = difference / (pooled * (1. - pooled) * (1. / n1 + 1. / n2)).sqrt();
+    let standard_error = (p1 * (1. - p1) / n1 + p2 * (1. - p2) / n2).sqrt();
+
+    let normal = Normal::standard();
+    ProportionTestResult {
+        statistic,
+        p_value: alternative.symmetric_p_value(statistic, |x| normal.cdf(x)),
+        estimate: difference,
+        confidence_interval: alternative.symmetric_interval(
+            difference,
+            standard_error,
+            level,
+            |q| normal.quantile(q),
+        ),
+    }
+}
+
+/// exact binomial test of `H0: successes / trials == p`.
+/// the two sided p-value sums the outcomes that are not more likely than the observed one,
+/// and the confidence interval is clopper–pearson
+pub fn binomial_test(
+    successes: u64,
+    trials: u64,
+    p: Num,
+    alternative: Alternative,
+    level: Num,
+) -> ProportionTestResult {
+    let distribution = Binomial::new(trials, p);
+    let upper_tail = |k: u64| if k == 0 { 1. } else { distribution.sf(k - 1) };
+
+    let p_value = match alternative {
+        Alternative::Less => distribution.cdf(successes),
+        Alternative::Greater => upper_tail(successes),
+        Alternative::TwoSided => {
+            let threshold = distribution.pmf(successes) * (1. + RELATIVE_ERROR);
+            (0..=trials)
+                .map(|k| distribution.pmf(k))
+                .filter(|d| *d <= threshold)
+                .sum::<Num>()
+                .min(1.)
+        }
+    };
+
+    let confidence_interval = match alternative {
+        Alternative::TwoSided => {
+            binomial_confidence_interval(successes, trials, level, BinomialInterval::ClopperPearson)
+        }
+        // a one sided interval at `level` is one side of a two sided one at `2 level - 1`
+        Alternative::Less => (
+            0.,
+            binomial_confidence_interval(
+                successes,
+                trials,
+                2. * level - 1.,
+                BinomialInterval::ClopperPearson,
+            )
+            .1,
+        ),
+        Alternative::Greater => (
+            binomial_confidence_interval(
+                successes,
+                trials,
+                2. * level - 1.,
+                BinomialInterval::ClopperPearson,
+            )
+            .0,
+            1.,
+        ),
+    };
+
+    ProportionTestResult {
+        statistic: successes as Num,
+        p_value,
+        estimate: successes as Num / trials as Num,
+        confidence_interval,
+    }
+}
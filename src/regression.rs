@@ -0,0 +1,988 @@
+//! least squares regression on several predictors and summaries of least squares fits
+use crate::distribution::{ChiSquared, FisherF, StudentT};
+use crate::estimate::Estimate;
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::{FTestResult, TestResult};
+use crate::matrix::Matrix;
+use crate::uni_dimentional::{mean, sort_float};
+
+const MAX_ITERATION: usize = 200;
+
+/// bound on the estimated AR(1) autocorrelation, which keeps the prais–winsten
+/// transformation of the first observation away from zero
+const MAX_AUTOCORRELATION: Num = 0.99;
+
+/// estimate of one regression coefficient with its t-test against zero
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coefficient {
+    pub estimate: Num,
+    pub standard_error: Num,
+    pub t_statistic: Num,
+    pub p_value: Num,
+    pub confidence_interval: (Num, Num),
+}
+
+/// diagnostics of a least squares fit. the coefficients start with the intercept when there is one
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionSummary {
+    pub coefficients: Vec<Coefficient>,
+    pub r_squared: Num,
+    pub adjusted_r_squared: Num,
+    /// estimate of the standard deviation of the errors
+    pub residual_standard_error: Num,
+    /// f-test of all coefficients other than the intercept being zero
+    pub f_test: FTestResult,
+    /// akaike information criterion from the gaussian log likelihood, counting the error variance as a parameter
+    pub aic: Num,
+    /// bayesian information criterion, like `aic`
+    pub bic: Num,
+    /// durbin–watson statistic of the residuals in the order of the observations. close to 2 without autocorrelation
+    pub durbin_watson: Num,
+    /// diagonal of the hat matrix
+    pub leverage: Vec<Num>,
+    pub cooks_distance: Vec<Num>,
+    /// externally studentized residuals, each scaled by the error estimated without its observation
+    pub studentized_residuals: Vec<Num>,
+    /// `1 / (1 - R^2)` of each predictor regressed on the other ones, without the intercept.
+    /// values above 5 or 10 point at collinearity
+    pub variance_inflation_factors: Vec<Num>,
+    /// ratio of the largest to the smallest singular value of the design matrix
+    pub condition_number: Num,
+    /// level of the confidence intervals
+    pub level: Num,
+    intercept: bool,
+    /// `(X'X)^-1`, the covariance of the coefficients divided by the error variance
+    unscaled_covariance: Matrix,
+}
+
+/// least squares solution of `design * coefficients = input_y` by householder qr.
+/// returns the coefficients and `(X'X)^-1 = R^-1 R^-T`
+pub(crate) fn least_squares(design: &Matrix, input_y: &Input) -> (Vec<Num>, Matrix) {
+    let (q, r) = design.qr();
+    let r_inverse = r
+        .upper_triangular_inverse()
+        .expect("the design matrix is rank deficient, some predictors are collinear");
+    let qty = (0..q.columns())
+        .map(|j| (0..q.rows()).map(|i| q[(i, j)] * input_y[i]).sum::<Num>())
+        .collect::<Vec<Num>>();
+    let coefficients = (0..r.rows())
+        .map(|i| (i..r.columns()).map(|j| r_inverse[(i, j)] * qty[j]).sum())
+        .collect();
+    (coefficients, r_inverse.multiply(&r_inverse.transpose()))
+}
+
+/// observed minus fitted values
+pub(crate) fn residuals(input_y: &Input, fitted_values: &Input) -> Vec<Num> {
+    input_y
+        .iter()
+        .zip(fitted_values.iter())
+        .map(|(y, fitted)| y - fitted)
+        .collect()
+}
+
+/// `intercept + sum(coefficient * predictor)` of one observation, the intercept being the first
+/// of `coefficients` when there is one
+pub(crate) fn linear_predictor(coefficients: &Input, intercept: bool, predictors: &Input) -> Num {
+    let (constant, slopes) = if intercept {
+        (coefficients[0], &coefficients[1..])
+    } else {
+        (0 as Num, coefficients)
+    };
+    assert_eq!(
+        predictors.len(),
+        slopes.len(),
+        "one value is needed for every predictor"
+    );
+    constant
+        + slopes
+            .iter()
+            .zip(predictors.iter())
+            .fold(0 as Num, |acc, (b, x)| acc + b * x)
+}
+
+/// `predict` applied to every observation of `predictors`, given as one slice per predictor
+pub(crate) fn predict_rows<F>(predictors: &[&Input], predict: F) -> Vec<Num>
+where
+    F: Fn(&Input) -> Num,
+{
+    let n = predictors.first().map_or(0, |p| p.len());
+    (0..n)
+        .map(|i| predict(&predictors.iter().map(|p| p[i]).collect::<Vec<Num>>()))
+        .collect()
+}
+
+/// `1 - RSS / TSS` of a fit from its fitted values and residuals
+pub(crate) fn r_squared(fitted_values: &Input, residuals: &Input) -> Num {
+    let observed = fitted_values
+        .iter()
+        .zip(residuals.iter())
+        .map(|(f, r)| f + r)
+        .collect::<Vec<Num>>();
+    let mean = observed.iter().sum::<Num>() / observed.len() as Num;
+    let tss = observed.iter().map(|y| (y - mean).powi(2)).sum::<Num>();
+    let rss = residuals.iter().map(|r| r * r).sum::<Num>();
+    1. - rss / tss
+}
+
+/// levenberg–marquardt least squares fit of the parameters of `model(x, parameters)`,
+/// starting from `initial`. the jacobian is taken by forward differences
+pub(crate) fn nonlinear_least_squares<F>(
+    input_x: &Input,
+    input_y: &Input,
+    initial: Vec<Num>,
+    model: F,
+) -> Vec<Num>
+where
+    F: Fn(Num, &[Num]) -> Num,
+{
+    let k = initial.len();
+    let sse = |parameters: &[Num]| {
+        input_x
+            .iter()
+            .zip(input_y.iter())
+            .map(|(x, y)| (y - model(*x, parameters)).powi(2))
+            .sum::<Num>()
+    };
+    let mut parameters = initial;
+    let mut current = sse(&parameters);
+    let mut damping = 1e-3;
+
+    for _ in 0..MAX_ITERATION {
+        let predicted = input_x
+            .iter()
+            .map(|x| model(*x, &parameters))
+            .collect::<Vec<Num>>();
+        let mut jacobian = Matrix::zeros(input_x.len(), k);
+        for j in 0..k {
+            let step = Num::EPSILON.sqrt() * parameters[j].abs().max(1.);
+            let mut shifted = parameters.clone();
+            shifted[j] += step;
+            for (i, x) in input_x.iter().enumerate() {
+                jacobian[(i, j)] = (model(*x, &shifted) - predicted[i]) / step;
+            }
+        }
+        let normal = jacobian.transpose().multiply(&jacobian);
+        let gradient = (0..k)
+            .map(|j| {
+                (0..input_x.len())
+                    .map(|i| jacobian[(i, j)] * (input_y[i] - predicted[i]))
+                    .sum::<Num>()
+            })
+            .collect::<Vec<Num>>();
+
+        // raise the damping until a step lowers the sum of squares
+        let improved = loop {
+            let mut damped = normal.clone();
+            (0..k).for_each(|j| damped[(j, j)] += damping * normal[(j, j)].max(Num::EPSILON));
+            let trial = damped.inverse().map(|inverse| {
+                (0..k)
+                    .map(|i| {
+                        parameters[i] + (0..k).map(|j| inverse[(i, j)] * gradient[j]).sum::<Num>()
+                    })
+                    .collect::<Vec<Num>>()
+            });
+            match trial {
+                Some(trial) if sse(&trial) < current => {
+                    let value = sse(&trial);
+                    damping /= 10.;
+                    break Some((trial, value));
+                }
+                _ if damping > 1e12 => break None,
+                _ => damping *= 10.,
+            }
+        };
+
+        match improved {
+            Some((trial, value)) => {
+                let converged = current - value <= current * 1e-15;
+                parameters = trial;
+                current = value;
+                if converged {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+    parameters
+}
+
+/// design matrix with one column per predictor, after a column of ones when `intercept` is set
+pub(crate) fn design_matrix(predictors: &[&Input], intercept: bool) -> Matrix {
+    let ones = vec![1 as Num; predictors.first().map_or(0, |p| p.len())];
+    let mut columns = Vec::with_capacity(predictors.len() + 1);
+    if intercept {
+        columns.push(&ones[..]);
+    }
+    columns.extend_from_slice(predictors);
+    Matrix::from_columns(&columns)
+}
+
+/// variance inflation factor of every column of the design matrix other than the intercept
+fn variance_inflation_factors(design: &Matrix, intercept: bool) -> Vec<Num> {
+    let first = if intercept { 1 } else { 0 };
+    let columns = (0..design.columns())
+        .map(|j| design.column(j))
+        .collect::<Vec<Vec<Num>>>();
+    (first..design.columns())
+        .map(|j| {
+            let others = (first..design.columns())
+                .filter(|k| *k != j)
+                .map(|k| &columns[k][..])
+                .collect::<Vec<&Input>>();
+            if others.is_empty() {
+                return 1.;
+            }
+            let target = &columns[j];
+            let (coefficients, _) = least_squares(&design_matrix(&others, intercept), target);
+            let model = MultipleRegression::new(coefficients, intercept, &others, target);
+            let center = if intercept {
+                target.iter().sum::<Num>() / target.len() as Num
+            } else {
+                0.
+            };
+            let tss = target.iter().map(|x| (x - center).powi(2)).sum::<Num>();
+            let rss = model.residuals.iter().map(|r| r * r).sum::<Num>();
+            tss / rss
+        })
+        .collect()
+}
+
+/// design matrix and residuals of a weighted fit turned into those of an unweighted one,
+/// by multiplying every row with the square root of its weight
+pub(crate) fn apply_weights(
+    design: &Matrix,
+    residuals: &Input,
+    weights: Option<&Input>,
+) -> (Matrix, Vec<Num>) {
+    match weights {
+        None => (design.clone(), residuals.to_vec()),
+        Some(weights) => {
+            let mut weighted = design.clone();
+            for (i, w) in weights.iter().enumerate() {
+                (0..design.columns()).for_each(|j| weighted[(i, j)] *= w.sqrt());
+            }
+            let residuals = residuals
+                .iter()
+                .zip(weights.iter())
+                .map(|(r, w)| r * w.sqrt())
+                .collect();
+            (weighted, residuals)
+        }
+    }
+}
+
+/// t-test of a coefficient against zero with `df` degrees of freedom
+fn coefficient_test(estimate: Num, standard_error: Num, df: Num, level: Num) -> Coefficient {
+    let t = StudentT::new(df);
+    let margin = t.quantile(1. - (1. - level) / 2.) * standard_error;
+    let t_statistic = estimate / standard_error;
+    Coefficient {
+        estimate,
+        standard_error,
+        t_statistic,
+        p_value: 2. * t.cdf(-t_statistic.abs()),
+        confidence_interval: (estimate - margin, estimate + margin),
+    }
+}
+
+/// summary of a least squares fit with design matrix `design`, whose first column is all ones when
+/// `intercept` is set. weighted fits pass their weights
+pub(crate) fn summarize(
+    design: &Matrix,
+    coefficients: &[Num],
+    fitted_values: &Input,
+    residuals: &Input,
+    weights: Option<&Input>,
+    intercept: bool,
+    level: Num,
+) -> RegressionSummary {
+    let n = design.rows() as Num;
+    let p = design.columns() as Num;
+    let df = n - p;
+    let unit = vec![1 as Num; design.rows()];
+    let weight = weights.unwrap_or(&unit);
+    let observed = fitted_values
+        .iter()
+        .zip(residuals.iter())
+        .map(|(f, r)| f + r)
+        .collect::<Vec<Num>>();
+    let (weighted_design, residuals) = apply_weights(design, residuals, weights);
+    let (_, unscaled_covariance) = least_squares(&weighted_design, &residuals);
+
+    let rss = residuals.iter().fold(0 as Num, |acc, r| acc + r * r);
+    // without an intercept the total sum of squares is taken around zero, like R does
+    let center = if intercept {
+        observed
+            .iter()
+            .zip(weight.iter())
+            .map(|(y, w)| y * w)
+            .sum::<Num>()
+            / weight.iter().sum::<Num>()
+    } else {
+        0.
+    };
+    let tss = observed
+        .iter()
+        .zip(weight.iter())
+        .fold(0 as Num, |acc, (y, w)| acc + w * (y - center).powi(2));
+    let variance = rss / df;
+    let model_df = if intercept { p - 1. } else { p };
+
+    let r_squared = 1. - rss / tss;
+    let adjusted_r_squared = 1. - (1. - r_squared) * (n - if intercept { 1. } else { 0. }) / df;
+    let f_statistic = (tss - rss) / model_df / variance;
+
+    let coefficients = coefficients
+        .iter()
+        .enumerate()
+        .map(|(j, estimate)| {
+            let standard_error = (variance * unscaled_covariance[(j, j)]).sqrt();
+            coefficient_test(*estimate, standard_error, df, level)
+        })
+        .collect();
+
+    let leverage = (0..design.rows())
+        .map(|i| quadratic_form(&unscaled_covariance, weighted_design.row(i)))
+        .collect::<Vec<Num>>();
+    let cooks_distance = residuals
+        .iter()
+        .zip(leverage.iter())
+        .map(|(e, h)| e * e * h / (p * variance * (1. - h).powi(2)))
+        .collect();
+    let studentized_residuals = residuals
+        .iter()
+        .zip(leverage.iter())
+        .map(|(e, h)| {
+            let deleted_variance = (df * variance - e * e / (1. - h)) / (df - 1.);
+            e / (deleted_variance * (1. - h)).sqrt()
+        })
+        .collect();
+
+    let log_likelihood = -n / 2. * ((2. * std::f64::consts::PI * rss / n).ln() + 1.)
+        + weight.iter().map(|w| w.ln()).sum::<Num>() / 2.;
+    let parameters = p + 1.;
+    let durbin_watson = residuals
+        .windows(2)
+        .fold(0 as Num, |acc, w| acc + (w[1] - w[0]).powi(2))
+        / rss;
+
+    let (_, singular_values, _) = design.svd();
+    let condition_number = singular_values[0] / singular_values[singular_values.len() - 1];
+
+    RegressionSummary {
+        coefficients,
+        r_squared,
+        adjusted_r_squared,
+        residual_standard_error: variance.sqrt(),
+        f_test: FTestResult {
+            statistic: f_statistic,
+            df1: model_df,
+            df2: df,
+            p_value: FisherF::new(model_df, df).sf(f_statistic),
+        },
+        aic: 2. * parameters - 2. * log_likelihood,
+        bic: parameters * n.ln() - 2. * log_likelihood,
+        durbin_watson,
+        leverage,
+        cooks_distance,
+        studentized_residuals,
+        variance_inflation_factors: variance_inflation_factors(design, intercept),
+        condition_number,
+        level,
+        intercept,
+        unscaled_covariance,
+    }
+}
+
+/// `x' A x`
+fn quadratic_form(a: &Matrix, x: &Input) -> Num {
+    (0..x.len())
+        .map(|i| x[i] * (0..x.len()).map(|j| a[(i, j)] * x[j]).sum::<Num>())
+        .sum()
+}
+
+impl RegressionSummary {
+    /// row of the design matrix for the given predictors
+    fn design_row(&self, predictors: &Input) -> Vec<Num> {
+        let row = if self.intercept {
+            std::iter::once(1.)
+                .chain(predictors.iter().copied())
+                .collect::<Vec<Num>>()
+        } else {
+            predictors.to_vec()
+        };
+        assert_eq!(
+            row.len(),
+            self.coefficients.len(),
+            "one value is needed for every predictor"
+        );
+        row
+    }
+
+    fn band(&self, predictors: &Input, new_observation: bool) -> Estimate {
+        let row = self.design_row(predictors);
+        let value = row
+            .iter()
+            .zip(self.coefficients.iter())
+            .fold(0 as Num, |acc, (x, c)| acc + x * c.estimate);
+        let spread =
+            quadratic_form(&self.unscaled_covariance, &row) + if new_observation { 1. } else { 0. };
+        let df = self.f_test.df2;
+        let margin = StudentT::new(df).quantile(1. - (1. - self.level) / 2.)
+            * self.residual_standard_error
+            * spread.sqrt();
+
+        Estimate {
+            value,
+            lower: value - margin,
+            upper: value + margin,
+            level: self.level,
+        }
+    }
+
+    /// confidence interval of the mean response at `predictors`, one value per predictor without the intercept
+    pub fn confidence_band(&self, predictors: &Input) -> Estimate {
+        self.band(predictors, false)
+    }
+
+    /// prediction interval of a new observation at `predictors`, one value per predictor without the intercept
+    pub fn prediction_band(&self, predictors: &Input) -> Estimate {
+        self.band(predictors, true)
+    }
+}
+
+/// linear model `intercept + sum(coefficient * predictor)` fitted by least squares
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultipleRegression {
+    /// one coefficient per predictor, after the intercept when the model has one
+    pub coefficients: Vec<Num>,
+    pub intercept: bool,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+    /// weights of the observations of a weighted least squares fit
+    pub weights: Option<Vec<Num>>,
+}
+
+impl MultipleRegression {
+    pub(crate) fn new(
+        coefficients: Vec<Num>,
+        intercept: bool,
+        predictors: &[&Input],
+        input_y: &Input,
+    ) -> Self {
+        let mut model = MultipleRegression {
+            coefficients,
+            intercept,
+            ..Default::default()
+        };
+        model.fitted_values = model.predict_many(predictors);
+        model.residuals = residuals(input_y, &model.fitted_values);
+        model
+    }
+
+    /// prediction for one observation, `predictors` holds one value per predictor
+    pub fn predict(&self, predictors: &Input) -> Num {
+        linear_predictor(&self.coefficients, self.intercept, predictors)
+    }
+
+    /// predictions for many observations, with one slice per predictor like `multiple_regression` takes
+    pub fn predict_many(&self, predictors: &[&Input]) -> Vec<Num> {
+        predict_rows(predictors, |row| self.predict(row))
+    }
+
+    /// least squares diagnostics of the model on the data it was fitted to, with intervals at `level`
+    pub fn summary(&self, predictors: &[&Input], level: Num) -> RegressionSummary {
+        summarize(
+            &design_matrix(predictors, self.intercept),
+            &self.coefficients,
+            &self.fitted_values,
+            &self.residuals,
+            self.weights.as_deref(),
+            self.intercept,
+            level,
+        )
+    }
+
+    /// coefficient tests with standard errors from a heteroscedasticity or autocorrelation
+    /// consistent covariance estimator
+    pub fn robust_coefficients(
+        &self,
+        predictors: &[&Input],
+        estimator: RobustCovariance,
+        level: Num,
+    ) -> Vec<Coefficient> {
+        robust_coefficients(
+            &design_matrix(predictors, self.intercept),
+            &self.coefficients,
+            &self.residuals,
+            self.weights.as_deref(),
+            estimator,
+            level,
+        )
+    }
+}
+
+/// ordinary least squares regression of `input_y` on several predictors, one slice per predictor.
+/// it is solved by householder qr rather than the normal equations for numerical stability.
+/// panics when the predictors are collinear
+pub fn multiple_regression(
+    predictors: &[&Input],
+    input_y: &Input,
+    intercept: bool,
+) -> MultipleRegression {
+    let (coefficients, _) = least_squares(&design_matrix(predictors, intercept), input_y);
+    MultipleRegression::new(coefficients, intercept, predictors, input_y)
+}
+
+/// weighted least squares regression, which minimizes `sum(weights * residuals^2)`.
+/// weights are usually the inverse variances of the observations
+pub fn weighted_multiple_regression(
+    predictors: &[&Input],
+    input_y: &Input,
+    weights: &Input,
+    intercept: bool,
+) -> MultipleRegression {
+    let (design, y) = apply_weights(
+        &design_matrix(predictors, intercept),
+        input_y,
+        Some(weights),
+    );
+    let (coefficients, _) = least_squares(&design, &y);
+    MultipleRegression {
+        weights: Some(weights.to_vec()),
+        ..MultipleRegression::new(coefficients, intercept, predictors, input_y)
+    }
+}
+
+/// feasible generalized least squares for errors that follow an AR(1) process, by iterated prais–winsten.
+/// returns the model, with fitted values and residuals on the original scale,
+/// and the estimated autocorrelation of the errors, kept within `±0.99`.
+/// the summary of the returned model treats the errors as independent
+pub fn ar1_regression(
+    predictors: &[&Input],
+    input_y: &Input,
+    intercept: bool,
+) -> (MultipleRegression, Num) {
+    let design = design_matrix(predictors, intercept);
+    let n = design.rows();
+    let mut model = multiple_regression(predictors, input_y, intercept);
+    let mut rho = 0 as Num;
+
+    for _ in 0..MAX_ITERATION {
+        let e = &model.residuals;
+        let next_rho = (1..n).map(|t| e[t] * e[t - 1]).sum::<Num>()
+            / (1..n).map(|t| e[t - 1] * e[t - 1]).sum::<Num>();
+        let next_rho = next_rho.clamp(-MAX_AUTOCORRELATION, MAX_AUTOCORRELATION);
+        let scale = (1. - next_rho * next_rho).sqrt();
+
+        let mut transformed = Matrix::zeros(n, design.columns());
+        let mut y = vec![0 as Num; n];
+        for j in 0..design.columns() {
+            transformed[(0, j)] = scale * design[(0, j)];
+            for t in 1..n {
+                transformed[(t, j)] = design[(t, j)] - next_rho * design[(t - 1, j)];
+            }
+        }
+        y[0] = scale * input_y[0];
+        for t in 1..n {
+            y[t] = input_y[t] - next_rho * input_y[t - 1];
+        }
+        let (coefficients, _) = least_squares(&transformed, &y);
+        model = MultipleRegression::new(coefficients, intercept, predictors, input_y);
+
+        let converged = (next_rho - rho).abs() < 1e-10;
+        rho = next_rho;
+        if converged {
+            break;
+        }
+    }
+    (model, rho)
+}
+
+/// estimators of the covariance of least squares coefficients that stay valid
+/// when the errors are heteroscedastic or autocorrelated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RobustCovariance {
+    /// white's estimator
+    HC0,
+    /// `HC0` scaled by `n / (n - p)`
+    HC1,
+    /// squared residuals divided by `1 - leverage`
+    HC2,
+    /// squared residuals divided by `(1 - leverage)^2`, close to the jackknife. the usual choice for small samples
+    HC3,
+    /// newey–west estimator with bartlett weights up to `lags`, for autocorrelated errors
+    NeweyWest { lags: usize },
+}
+
+/// coefficient tests from the sandwich covariance `(X'X)^-1 X' Omega X (X'X)^-1`
+pub(crate) fn robust_coefficients(
+    design: &Matrix,
+    coefficients: &[Num],
+    residuals: &Input,
+    weights: Option<&Input>,
+    estimator: RobustCovariance,
+    level: Num,
+) -> Vec<Coefficient> {
+    let (design, e) = apply_weights(design, residuals, weights);
+    let (n, p) = (design.rows(), design.columns());
+    let (_, bread) = least_squares(&design, &e);
+    let leverage = (0..n)
+        .map(|i| quadratic_form(&bread, design.row(i)))
+        .collect::<Vec<Num>>();
+
+    let mut meat = Matrix::zeros(p, p);
+    let mut add = |i: usize, k: usize, scale: Num| {
+        for a in 0..p {
+            for b in 0..p {
+                meat[(a, b)] += scale * design[(i, a)] * design[(k, b)];
+            }
+        }
+    };
+    for i in 0..n {
+        let omega = match estimator {
+            RobustCovariance::HC0 | RobustCovariance::NeweyWest { .. } => e[i] * e[i],
+            RobustCovariance::HC1 => e[i] * e[i] * n as Num / (n - p) as Num,
+            RobustCovariance::HC2 => e[i] * e[i] / (1. - leverage[i]),
+            RobustCovariance::HC3 => e[i] * e[i] / (1. - leverage[i]).powi(2),
+        };
+        add(i, i, omega);
+    }
+    if let RobustCovariance::NeweyWest { lags } = estimator {
+        for lag in 1..=lags.min(n - 1) {
+            let weight = 1. - lag as Num / (lags + 1) as Num;
+            for t in lag..n {
+                let scale = weight * e[t] * e[t - lag];
+                add(t, t - lag, scale);
+                add(t - lag, t, scale);
+            }
+        }
+    }
+
+    let covariance = bread.multiply(&meat).multiply(&bread);
+    coefficients
+        .iter()
+        .enumerate()
+        .map(|(j, estimate)| {
+            coefficient_test(*estimate, covariance[(j, j)].sqrt(), (n - p) as Num, level)
+        })
+        .collect()
+}
+
+/// `n R^2` of the regression of the squared residuals on `regressors` and an intercept
+fn auxiliary_lm_test(regressors: &[&Input], residuals: &Input) -> TestResult {
+    let squared = residuals.iter().map(|e| e * e).collect::<Vec<Num>>();
+    let auxiliary = multiple_regression(regressors, &squared, true);
+    let statistic =
+        residuals.len() as Num * r_squared(&auxiliary.fitted_values, &auxiliary.residuals);
+    let df = regressors.len() as Num;
+    TestResult {
+        statistic,
+        df: Some(df),
+        p_value: ChiSquared::new(df).sf(statistic),
+    }
+}
+
+/// koenker's studentized breusch–pagan test of `H0: the errors are homoscedastic`,
+/// from the residuals of a fit on `predictors`
+pub fn breusch_pagan(predictors: &[&Input], residuals: &Input) -> TestResult {
+    auxiliary_lm_test(predictors, residuals)
+}
+
+/// white's test of `H0: the errors are homoscedastic`, which also regresses the squared residuals
+/// on the squares and cross products of the predictors. indicator predictors, whose squares
+/// equal themselves, make the auxiliary regression collinear and must be left out
+pub fn white_test(predictors: &[&Input], residuals: &Input) -> TestResult {
+    let k = predictors.len();
+    let mut terms = predictors
+        .iter()
+        .map(|p| p.to_vec())
+        .collect::<Vec<Vec<Num>>>();
+    for i in 0..k {
+        for j in i..k {
+            terms.push(
+                predictors[i]
+                    .iter()
+                    .zip(predictors[j].iter())
+                    .map(|(a, b)| a * b)
+                    .collect(),
+            );
+        }
+    }
+    let regressors = terms.iter().map(|t| &t[..]).collect::<Vec<&Input>>();
+    auxiliary_lm_test(&regressors, residuals)
+}
+
+/// polynomial `sum(coefficients[k] * x^k)` fitted by least squares.
+/// the fit uses polynomials orthogonal over the data, built by forsythe's three term recurrence,
+/// which stay well conditioned for high degrees where powers of x do not
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PolynomialRegression {
+    /// coefficients of the powers of x from the constant up
+    pub coefficients: Vec<Num>,
+    /// predictions on the data the model was fitted to
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+    /// recurrence `P_k+1(x) = (x - alpha_k) P_k(x) - beta_k P_k-1(x)` of the orthogonal basis
+    alphas: Vec<Num>,
+    betas: Vec<Num>,
+    /// coefficients of the orthogonal polynomials
+    orthogonal_coefficients: Vec<Num>,
+}
+
+/// ways to choose the degree of a polynomial regression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeSelection {
+    /// lowest mean squared error of prediction over `folds` folds. observation `i` is in fold `i % folds`.
+    /// degrees some training set has too few distinct x for are never chosen
+    CrossValidation { folds: usize },
+    /// lowest akaike information criterion
+    Aic,
+}
+
+/// least squares polynomial of the given degree
+pub fn polynomial_regression(
+    input_x: &Input,
+    input_y: &Input,
+    degree: usize,
+) -> PolynomialRegression {
+    let n = input_x.len();
+    let (mut alphas, mut betas, mut orthogonal_coefficients) = (vec![], vec![], vec![]);
+    let mut previous = vec![0 as Num; n];
+    let mut current = vec![1 as Num; n];
+    let mut previous_norm = 1 as Num;
+
+    for k in 0..=degree {
+        let norm = current.iter().map(|p| p * p).sum::<Num>();
+        assert!(
+            norm > Num::EPSILON * n as Num,
+            "the degree must be lower than the number of distinct values of input_x"
+        );
+        let projection = current
+            .iter()
+            .zip(input_y.iter())
+            .map(|(p, y)| p * y)
+            .sum::<Num>();
+        orthogonal_coefficients.push(projection / norm);
+        if k == degree {
+            break;
+        }
+
+        let alpha = current
+            .iter()
+            .zip(input_x.iter())
+            .map(|(p, x)| x * p * p)
+            .sum::<Num>()
+            / norm;
+        let beta = if k == 0 { 0. } else { norm / previous_norm };
+        let next = (0..n)
+            .map(|i| (input_x[i] - alpha) * current[i] - beta * previous[i])
+            .collect::<Vec<Num>>();
+        alphas.push(alpha);
+        betas.push(beta);
+        previous = std::mem::replace(&mut current, next);
+        previous_norm = norm;
+    }
+
+    let basis = power_basis(&alphas, &betas);
+    let coefficients = (0..=degree)
+        .map(|j| {
+            (0..=degree)
+                .map(|k| basis[(j, k)] * orthogonal_coefficients[k])
+                .sum()
+        })
+        .collect();
+
+    let mut model = PolynomialRegression {
+        coefficients,
+        alphas,
+        betas,
+        orthogonal_coefficients,
+        ..Default::default()
+    };
+    model.fitted_values = model.predict_many(input_x);
+    model.residuals = residuals(input_y, &model.fitted_values);
+    model
+}
+
+/// coefficients of the powers of x in the orthogonal polynomials of the recurrence,
+/// with `x^j` of `P_k` at row `j` and column `k`
+fn power_basis(alphas: &Input, betas: &Input) -> Matrix {
+    let size = alphas.len() + 1;
+    let mut basis = Matrix::zeros(size, size);
+    let mut previous = vec![0 as Num; size];
+    let mut current = vec![0 as Num; size];
+    current[0] = 1.;
+    for k in 0..size {
+        (0..size).for_each(|j| basis[(j, k)] = current[j]);
+        if k + 1 < size {
+            let mut next = vec![0 as Num; size];
+            for j in 0..=k {
+                next[j + 1] += current[j];
+                next[j] -= alphas[k] * current[j] + betas[k] * previous[j];
+            }
+            previous = std::mem::replace(&mut current, next);
+        }
+    }
+    basis
+}
+
+impl PolynomialRegression {
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// evaluates the fitted polynomial through the recurrence of its orthogonal basis
+    pub fn predict(&self, x: Num) -> Num {
+        let (mut previous, mut current) = (0 as Num, 1 as Num);
+        let mut value = self.orthogonal_coefficients[0];
+        for k in 0..self.alphas.len() {
+            let next = (x - self.alphas[k]) * current - self.betas[k] * previous;
+            previous = current;
+            current = next;
+            value += self.orthogonal_coefficients[k + 1] * current;
+        }
+        value
+    }
+
+    pub fn predict_many(&self, input_x: &Input) -> Vec<Num> {
+        input_x.iter().map(|x| self.predict(*x)).collect()
+    }
+
+    /// `order`-th derivative of the fitted polynomial at `x`
+    pub fn derivative(&self, x: Num, order: usize) -> Num {
+        self.coefficients
+            .iter()
+            .enumerate()
+            .skip(order)
+            .rev()
+            .fold(0 as Num, |acc, (k, c)| {
+                // k! / (k - order)! from differentiating x^k `order` times
+                let factor = (k - order + 1..=k).fold(1 as Num, |f, i| f * i as Num);
+                acc * x + c * factor
+            })
+    }
+
+    /// share of the variance of the data the polynomial explains
+    pub fn r_squared(&self) -> Num {
+        r_squared(&self.fitted_values, &self.residuals)
+    }
+
+    /// values of the orthogonal polynomials at `x`, from the constant up
+    fn orthogonal_values(&self, x: Num) -> Vec<Num> {
+        let mut values = vec![1 as Num];
+        let (mut previous, mut current) = (0 as Num, 1 as Num);
+        for k in 0..self.alphas.len() {
+            let next = (x - self.alphas[k]) * current - self.betas[k] * previous;
+            previous = current;
+            current = next;
+            values.push(current);
+        }
+        values
+    }
+
+    /// least squares diagnostics, with the powers of x as predictors. the fit is summarized on its
+    /// orthogonal basis, whose covariance is diagonal, and the covariance is carried over to the powers.
+    /// the condition number is the one of the orthogonal basis
+    pub fn summary(&self, input_x: &Input, level: Num) -> RegressionSummary {
+        let rows = input_x
+            .iter()
+            .map(|x| self.orthogonal_values(*x))
+            .collect::<Vec<Vec<Num>>>();
+        let rows = rows.iter().map(|r| &r[..]).collect::<Vec<&Input>>();
+        let mut summary = summarize(
+            &Matrix::from_rows(&rows),
+            &self.orthogonal_coefficients,
+            &self.fitted_values,
+            &self.residuals,
+            None,
+            true,
+            level,
+        );
+
+        let basis = power_basis(&self.alphas, &self.betas);
+        let covariance = basis
+            .multiply(&summary.unscaled_covariance)
+            .multiply(&basis.transpose());
+        let variance = summary.residual_standard_error.powi(2);
+        let df = summary.f_test.df2;
+        summary.coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, estimate)| {
+                let standard_error = (variance * covariance[(j, j)]).sqrt();
+                coefficient_test(*estimate, standard_error, df, level)
+            })
+            .collect();
+        // the diagonal of (X'X)^-1 is VIF_j / sum((x_j - mean)^2) for every slope of a model with an intercept
+        summary.variance_inflation_factors = (1..=self.degree())
+            .map(|j| {
+                let powers = input_x
+                    .iter()
+                    .map(|x| x.powi(j as i32))
+                    .collect::<Vec<Num>>();
+                let center = mean(&powers);
+                covariance[(j, j)] * powers.iter().map(|p| (p - center).powi(2)).sum::<Num>()
+            })
+            .collect();
+        summary.unscaled_covariance = covariance;
+        summary
+    }
+}
+
+/// degree from 0 to `max_degree` that `criterion` prefers
+pub fn select_polynomial_degree(
+    input_x: &Input,
+    input_y: &Input,
+    max_degree: usize,
+    criterion: DegreeSelection,
+) -> usize {
+    let n = input_x.len();
+    if let DegreeSelection::CrossValidation { folds } = criterion {
+        assert!(folds >= 2, "cross validation needs at least 2 folds");
+    }
+    let score = |degree: usize| match criterion {
+        DegreeSelection::Aic => {
+            let model = polynomial_regression(input_x, input_y, degree);
+            let rss = model.residuals.iter().map(|r| r * r).sum::<Num>();
+            let parameters = (degree + 2) as Num;
+            n as Num * (rss / n as Num).ln() + 2. * parameters
+        }
+        DegreeSelection::CrossValidation { folds } => {
+            let squared_error = (0..folds)
+                .map(|fold| {
+                    let (train, test): (Vec<usize>, Vec<usize>) =
+                        (0..n).partition(|i| i % folds != fold);
+                    let x = train.iter().map(|i| input_x[*i]).collect::<Vec<Num>>();
+                    let y = train.iter().map(|i| input_y[*i]).collect::<Vec<Num>>();
+                    let mut distinct = sort_float(&x);
+                    distinct.dedup();
+                    if distinct.len() <= degree {
+                        return Num::INFINITY;
+                    }
+                    let model = polynomial_regression(&x, &y, degree);
+                    test.iter()
+                        .map(|i| (input_y[*i] - model.predict(input_x[*i])).powi(2))
+                        .sum::<Num>()
+                })
+                .sum::<Num>();
+            squared_error / n as Num
+        }
+    };
+
+    (0..=max_degree)
+        .map(|degree| (degree, score(degree)))
+        .fold((0, Num::INFINITY), |best, (degree, value)| {
+            if value < best.1 {
+                (degree, value)
+            } else {
+                best
+            }
+        })
+        .0
+}
@@ -0,0 +1,389 @@
+//! penalized least squares: ridge, lasso and elastic net fitted by coordinate descent.
+//! predictors are standardized before fitting and coefficients are reported on their original scale
+use crate::generic_types::{Input, Num, OrdFloat};
+use crate::regression::{predict_rows, residuals};
+
+const MAX_SWEEPS: usize = 10_000;
+
+/// coordinate descent stops once no standardized coefficient changes over a sweep by more than
+/// this share of the standard deviation of the response
+const TOLERANCE: Num = 1e-10;
+
+/// penalty added to `sum(residuals^2) / (2 n)`, scaled by lambda
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    /// `|b|^2 / 2`, which shrinks correlated coefficients towards each other
+    Ridge,
+    /// `|b|_1`, which sets some coefficients exactly to zero
+    Lasso,
+    /// `alpha |b|_1 + (1 - alpha) |b|^2 / 2`, between ridge (`alpha = 0`) and lasso (`alpha = 1`)
+    ElasticNet { alpha: Num },
+}
+
+impl Penalty {
+    fn alpha(self) -> Num {
+        match self {
+            Penalty::Ridge => 0.,
+            Penalty::Lasso => 1.,
+            Penalty::ElasticNet { alpha } => alpha,
+        }
+    }
+}
+
+/// linear model fitted with a penalty on its coefficients
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegularizedRegression {
+    pub intercept: Num,
+    /// one coefficient per predictor, on the original scale of the predictors
+    pub coefficients: Vec<Num>,
+    pub lambda: Num,
+    /// predictions of the model for the training data
+    pub fitted_values: Vec<Num>,
+    /// observed minus fitted values
+    pub residuals: Vec<Num>,
+}
+
+impl RegularizedRegression {
+    fn new(
+        intercept: Num,
+        coefficients: Vec<Num>,
+        lambda: Num,
+        predictors: &[&Input],
+        y: &Input,
+    ) -> Self {
+        let mut model = RegularizedRegression {
+            intercept,
+            coefficients,
+            lambda,
+            ..Default::default()
+        };
+        model.fitted_values = model.predict_many(predictors);
+        model.residuals = residuals(y, &model.fitted_values);
+        model
+    }
+
+    /// prediction for one observation, given as one value per predictor
+    pub fn predict(&self, x: &Input) -> Num {
+        self.intercept
+            + self
+                .coefficients
+                .iter()
+                .zip(x.iter())
+                .map(|(b, x)| b * x)
+                .sum::<Num>()
+    }
+
+    /// predictions for many observations, given as one slice per predictor
+    pub fn predict_many(&self, predictors: &[&Input]) -> Vec<Num> {
+        predict_rows(predictors, |row| self.predict(row))
+    }
+
+    /// number of coefficients that are not zero
+    pub fn nonzero(&self) -> usize {
+        self.coefficients.iter().filter(|b| **b != 0.).count()
+    }
+}
+
+/// predictors centered and scaled to unit variance, computed with divisor n, and the centered response.
+/// constant predictors are left as zeros, so their coefficients stay zero
+struct Standardized {
+    columns: Vec<Vec<Num>>,
+    means: Vec<Num>,
+    scales: Vec<Num>,
+    y: Vec<Num>,
+    y_mean: Num,
+}
+
+impl Standardized {
+    fn new(predictors: &[&Input], y: &Input) -> Self {
+        let n = y.len() as Num;
+        let mut means = Vec::with_capacity(predictors.len());
+        let mut scales = Vec::with_capacity(predictors.len());
+        let columns = predictors
+            .iter()
+            .map(|p| {
+                let mean = p.iter().sum::<Num>() / n;
+                let scale = (p.iter().map(|x| (x - mean).powi(2)).sum::<Num>() / n).sqrt();
+                means.push(mean);
+                scales.push(scale);
+                if scale == 0. {
+                    vec![0 as Num; p.len()]
+                } else {
+                    p.iter().map(|x| (x - mean) / scale).collect()
+                }
+            })
+            .collect();
+        let y_mean = y.iter().sum::<Num>() / n;
+        Standardized {
+            columns,
+            means,
+            scales,
+            y: y.iter().map(|y| y - y_mean).collect(),
+            y_mean,
+        }
+    }
+
+    /// smallest lambda at which every coefficient is zero. ridge never zeroes them,
+    /// so it uses the lambda of an elastic net with `alpha = 0.001` like glmnet does
+    fn lambda_max(&self, alpha: Num) -> Num {
+        let n = self.y.len() as Num;
+        self.columns
+            .iter()
+            .map(|z| (z.iter().zip(self.y.iter()).map(|(z, y)| z * y).sum::<Num>() / n).abs())
+            .fold(0 as Num, Num::max)
+            / alpha.max(1e-3)
+    }
+
+    /// coordinate descent from the standardized coefficients `beta`, which are updated in place
+    fn descend(&self, beta: &mut [Num], lambda: Num, alpha: Num) {
+        let n = self.y.len() as Num;
+        let mut residuals = self.y.clone();
+        for (z, b) in self.columns.iter().zip(beta.iter()) {
+            residuals
+                .iter_mut()
+                .zip(z.iter())
+                .for_each(|(r, z)| *r -= b * z);
+        }
+        let tolerance = TOLERANCE * (self.y.iter().map(|y| y * y).sum::<Num>() / n).sqrt();
+        let threshold = lambda * alpha;
+        let shrinkage = 1. + lambda * (1. - alpha);
+
+        for _ in 0..MAX_SWEEPS {
+            let mut largest_change = 0 as Num;
+            for (z, b) in self.columns.iter().zip(beta.iter_mut()) {
+                let rho = z
+                    .iter()
+                    .zip(residuals.iter())
+                    .map(|(z, r)| z * r)
+                    .sum::<Num>()
+                    / n
+                    + *b;
+                let next = rho.signum() * (rho.abs() - threshold).max(0.) / shrinkage;
+                let change = next - *b;
+                if change != 0. {
+                    residuals
+                        .iter_mut()
+                        .zip(z.iter())
+                        .for_each(|(r, z)| *r -= change * z);
+                    largest_change = largest_change.max(change.abs());
+                    *b = next;
+                }
+            }
+            if largest_change <= tolerance {
+                break;
+            }
+        }
+    }
+
+    /// intercept and coefficients on the original scale
+    fn unstandardize(&self, beta: &[Num]) -> (Num, Vec<Num>) {
+        let coefficients = beta
+            .iter()
+            .zip(self.scales.iter())
+            .map(|(b, s)| if *s == 0. { 0. } else { b / s })
+            .collect::<Vec<Num>>();
+        let intercept = self.y_mean
+            - coefficients
+                .iter()
+                .zip(self.means.iter())
+                .map(|(b, m)| b * m)
+                .sum::<Num>();
+        (intercept, coefficients)
+    }
+
+    /// `n_lambdas` log spaced lambdas from `lambda_max` down to a small fraction of it
+    fn lambdas(&self, alpha: Num, n_lambdas: usize) -> Vec<Num> {
+        let (n, p) = (self.y.len(), self.columns.len());
+        let max = self.lambda_max(alpha);
+        let ratio: Num = if n > p { 1e-4 } else { 1e-2 };
+        (0..n_lambdas)
+            .map(|k| max * ratio.powf(k as Num / (n_lambdas - 1).max(1) as Num))
+            .collect()
+    }
+
+    /// standardized coefficients along `lambdas`, each fit warm started from the previous one
+    fn path(&self, lambdas: &[Num], alpha: Num) -> Vec<Vec<Num>> {
+        let mut beta = vec![0 as Num; self.columns.len()];
+        lambdas
+            .iter()
+            .map(|lambda| {
+                self.descend(&mut beta, *lambda, alpha);
+                beta.clone()
+            })
+            .collect()
+    }
+}
+
+/// fits `y` on `predictors`, one slice per variable, minimizing
+/// `sum(residuals^2) / (2 n) + lambda * penalty(b)` over the standardized coefficients `b`
+pub fn regularized_regression(
+    predictors: &[&Input],
+    y: &Input,
+    penalty: Penalty,
+    lambda: Num,
+) -> RegularizedRegression {
+    let standardized = Standardized::new(predictors, y);
+    let mut beta = vec![0 as Num; predictors.len()];
+    standardized.descend(&mut beta, lambda, penalty.alpha());
+    let (intercept, coefficients) = standardized.unstandardize(&beta);
+    RegularizedRegression::new(intercept, coefficients, lambda, predictors, y)
+}
+
+/// coefficients of a penalized regression along a decreasing sequence of lambdas
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularizationPath {
+    pub penalty: Penalty,
+    pub lambdas: Vec<Num>,
+    /// intercept of the model at every lambda
+    pub intercepts: Vec<Num>,
+    /// coefficients on the original scale, one row per lambda
+    pub coefficients: Vec<Vec<Num>>,
+}
+
+impl RegularizationPath {
+    /// the model at `lambdas[index]`
+    pub fn model(&self, index: usize, predictors: &[&Input], y: &Input) -> RegularizedRegression {
+        RegularizedRegression::new(
+            self.intercepts[index],
+            self.coefficients[index].clone(),
+            self.lambdas[index],
+            predictors,
+            y,
+        )
+    }
+
+    /// the path as comma separated values, with a header `lambda,intercept,b1,b2,...` and one line per lambda
+    pub fn to_csv(&self) -> String {
+        let p = self.coefficients.first().map_or(0, |c| c.len());
+        let mut csv = String::from("lambda,intercept");
+        (1..=p).for_each(|j| csv.push_str(&format!(",b{}", j)));
+        csv.push('\n');
+        for (k, lambda) in self.lambdas.iter().enumerate() {
+            csv.push_str(&format!("{},{}", lambda, self.intercepts[k]));
+            self.coefficients[k]
+                .iter()
+                .for_each(|b| csv.push_str(&format!(",{}", b)));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// warm started fits along `n_lambdas` log spaced lambdas, from the smallest one that zeroes every coefficient
+/// down to `1e-4` of it, or `1e-2` of it when there are at least as many predictors as observations
+pub fn regularization_path(
+    predictors: &[&Input],
+    y: &Input,
+    penalty: Penalty,
+    n_lambdas: usize,
+) -> RegularizationPath {
+    let standardized = Standardized::new(predictors, y);
+    let alpha = penalty.alpha();
+    let lambdas = standardized.lambdas(alpha, n_lambdas);
+    let (intercepts, coefficients) = standardized
+        .path(&lambdas, alpha)
+        .iter()
+        .map(|beta| standardized.unstandardize(beta))
+        .unzip();
+    RegularizationPath {
+        penalty,
+        lambdas,
+        intercepts,
+        coefficients,
+    }
+}
+
+/// k-fold cross-validation of a regularization path
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularizationCrossValidation {
+    pub lambdas: Vec<Num>,
+    /// mean squared prediction error at every lambda, averaged over the folds
+    pub mean_errors: Vec<Num>,
+    /// standard error of `mean_errors` across the folds
+    pub standard_errors: Vec<Num>,
+    /// lambda with the smallest mean error
+    pub lambda_min: Num,
+    /// largest lambda whose mean error is within one standard error of the smallest, a sparser and more stable choice
+    pub lambda_1se: Num,
+}
+
+/// cross-validates the lambdas of `regularization_path` over `folds` folds, observation `i` going to fold `i % folds`.
+/// `folds` is at least 2 and at most the number of observations
+pub fn cross_validate_regularization(
+    predictors: &[&Input],
+    y: &Input,
+    penalty: Penalty,
+    n_lambdas: usize,
+    folds: usize,
+) -> RegularizationCrossValidation {
+    let n = y.len();
+    assert!(
+        folds >= 2 && folds <= n,
+        "cross validation needs between 2 folds and one fold per observation"
+    );
+    let alpha = penalty.alpha();
+    let lambdas = Standardized::new(predictors, y).lambdas(alpha, n_lambdas);
+
+    let errors = (0..folds)
+        .map(|fold| {
+            let (train, test): (Vec<usize>, Vec<usize>) = (0..n).partition(|i| i % folds != fold);
+            let subset = |input: &Input, indices: &[usize]| {
+                indices.iter().map(|i| input[*i]).collect::<Vec<Num>>()
+            };
+            let train_columns = predictors
+                .iter()
+                .map(|p| subset(p, &train))
+                .collect::<Vec<Vec<Num>>>();
+            let train_predictors = train_columns
+                .iter()
+                .map(|c| &c[..])
+                .collect::<Vec<&Input>>();
+            let standardized = Standardized::new(&train_predictors, &subset(y, &train));
+
+            standardized
+                .path(&lambdas, alpha)
+                .iter()
+                .map(|beta| {
+                    let (intercept, coefficients) = standardized.unstandardize(beta);
+                    test.iter()
+                        .map(|i| {
+                            let fitted = intercept
+                                + coefficients
+                                    .iter()
+                                    .zip(predictors.iter())
+                                    .map(|(b, p)| b * p[*i])
+                                    .sum::<Num>();
+                            (y[*i] - fitted).powi(2)
+                        })
+                        .sum::<Num>()
+                        / test.len() as Num
+                })
+                .collect::<Vec<Num>>()
+        })
+        .collect::<Vec<Vec<Num>>>();
+
+    let k = folds as Num;
+    let (mean_errors, standard_errors): (Vec<Num>, Vec<Num>) = (0..lambdas.len())
+        .map(|l| {
+            let mean = errors.iter().map(|e| e[l]).sum::<Num>() / k;
+            let variance = errors.iter().map(|e| (e[l] - mean).powi(2)).sum::<Num>() / (k - 1.);
+            (mean, (variance / k).sqrt())
+        })
+        .unzip();
+
+    let best = (0..lambdas.len())
+        .min_by_key(|l| OrdFloat(mean_errors[*l]))
+        .expect("at least one lambda");
+    let limit = mean_errors[best] + standard_errors[best];
+    let one_se = (0..=best)
+        .find(|l| mean_errors[*l] <= limit)
+        .unwrap_or(best);
+
+    RegularizationCrossValidation {
+        lambda_min: lambdas[best],
+        lambda_1se: lambdas[one_se],
+        lambdas,
+        mean_errors,
+        standard_errors,
+    }
+}
@@ -0,0 +1,653 @@
+//! resampling methods: the bootstrap, the jackknife and permutation tests
+use crate::bi_dimentional::correlation;
+use crate::distribution::special::ln_choose;
+use crate::distribution::Normal;
+use crate::estimate::{interpolated_quantile, Estimate};
+use crate::generic_types::{Input, Num};
+use crate::hypothesis::{Alternative, TestResult};
+use crate::uni_dimentional::{argsort, mean, sort_float, standard_deviation_sample};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// relative tolerance under which a permuted statistic counts as equal to the observed one
+const TOLERANCE: Num = 1e-9;
+
+/// statistic of the resample made of the observations at the given indices
+type IndexStatistic<'a> = Box<dyn Fn(&[usize]) -> Num + 'a>;
+
+/// bootstrap distribution of a statistic. seed the random number generator,
+/// e.g. with `rand::rngs::StdRng::seed_from_u64`, to make it reproducible
+pub struct Bootstrap<'a> {
+    statistic: IndexStatistic<'a>,
+    size: usize,
+    /// statistic of the original sample
+    pub estimate: Num,
+    /// statistic of every resample, in the order they were drawn
+    pub replicates: Vec<Num>,
+    /// standard deviation of the replicates
+    pub standard_error: Num,
+    /// mean of the replicates minus the estimate
+    pub bias: Num,
+    /// bit set of the observations that appear in each resample
+    members: Vec<Vec<u64>>,
+}
+
+/// jackknife estimates of the bias and standard error of a statistic
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jackknife {
+    /// statistic of the original sample
+    pub estimate: Num,
+    /// statistic of every sample with observations deleted
+    pub replicates: Vec<Num>,
+    pub bias: Num,
+    pub standard_error: Num,
+}
+
+/// jackknife-after-bootstrap diagnostics, how much each observation drives the bootstrap
+#[derive(Debug, Clone, PartialEq)]
+pub struct JackknifeAfterBootstrap {
+    /// bootstrap standard error from the resamples that do not contain each observation.
+    /// NaN when fewer than two resamples leave the observation out
+    pub standard_errors: Vec<Num>,
+    /// jackknife standard error of the bootstrap standard error
+    pub standard_error: Num,
+    /// jackknife influence value `(n - 1) (mean - leave one out statistic)` of each observation
+    pub influence: Vec<Num>,
+}
+
+/// `size` indices drawn with replacement from `0..size`
+fn draw<R: Rng>(size: usize, rng: &mut R) -> Vec<usize> {
+    (0..size).map(|_| rng.gen_range(0, size)).collect()
+}
+
+/// bit set of the distinct indices
+fn membership(indices: &[usize], size: usize) -> Vec<u64> {
+    let mut bits = vec![0u64; (size + 63) / 64];
+    indices.iter().for_each(|i| bits[i / 64] |= 1 << (i % 64));
+    bits
+}
+
+/// position of every observation in the sorted input
+fn sorted_positions(input: &Input) -> Vec<usize> {
+    let mut positions = vec![0; input.len()];
+    argsort(input)
+        .iter()
+        .enumerate()
+        .for_each(|(position, i)| positions[*i] = position);
+    positions
+}
+
+/// statistic of the observations at the given indices, passed to `statistic` sorted
+fn sorted_statistic<'a, F>(input: &Input, statistic: F) -> impl Fn(&[usize]) -> Num + 'a
+where
+    F: Fn(&Input) -> Num + 'a,
+{
+    let sorted = sort_float(input);
+    let positions = sorted_positions(input);
+    move |indices: &[usize]| {
+        let mut at = indices
+            .iter()
+            .map(|i| positions[*i])
+            .collect::<Vec<usize>>();
+        at.sort_unstable();
+        let resample = at.iter().map(|i| sorted[*i]).collect::<Vec<Num>>();
+        statistic(&resample)
+    }
+}
+
+/// delete-d jackknife of a statistic of the observations at the given indices
+fn delete_d<F>(size: usize, d: usize, statistic: F) -> Jackknife
+where
+    F: Fn(&[usize]) -> Num,
+{
+    assert!(
+        d >= 1 && d < size,
+        "d must be between 1 and the sample size - 1"
+    );
+    let estimate = statistic(&(0..size).collect::<Vec<usize>>());
+    let mut replicates = Vec::new();
+    for_each_combination(size, d, |deleted| {
+        let mut kept = Vec::with_capacity(size - d);
+        let mut deleted = deleted.iter().peekable();
+        for i in 0..size {
+            if deleted.peek() == Some(&&i) {
+                deleted.next();
+            } else {
+                kept.push(i);
+            }
+        }
+        replicates.push(statistic(&kept));
+    });
+
+    let (n, d) = (size as Num, d as Num);
+    let replicate_mean = mean(&replicates);
+    let squares = replicates
+        .iter()
+        .fold(0 as Num, |acc, t| acc + (t - replicate_mean).powi(2));
+
+    Jackknife {
+        estimate,
+        bias: (n - d) / d * (replicate_mean - estimate),
+        standard_error: ((n - d) / (d * replicates.len() as Num) * squares).sqrt(),
+        replicates,
+    }
+}
+
+/// leave-one-out jackknife of a statistic.
+/// the input and every subsample are passed to `statistic` sorted, like `bootstrap` does
+pub fn jackknife<F>(input: &Input, statistic: F) -> Jackknife
+where
+    F: Fn(&Input) -> Num,
+{
+    delete_d(input.len(), 1, sorted_statistic(input, statistic))
+}
+
+/// delete-d jackknife, which leaves out every subset of `d` observations.
+/// it is consistent for non smooth statistics such as the median when `d` grows faster than `sqrt(n)`,
+/// but there are `n choose d` subsets
+pub fn delete_d_jackknife<F>(input: &Input, statistic: F, d: usize) -> Jackknife
+where
+    F: Fn(&Input) -> Num,
+{
+    delete_d(input.len(), d, sorted_statistic(input, statistic))
+}
+
+impl Jackknife {
+    /// the estimate minus its jackknife estimate of bias
+    pub fn bias_corrected(&self) -> Num {
+        self.estimate - self.bias
+    }
+}
+
+/// bootstrap of a statistic of one sample.
+/// the input and every resample are sorted before they are passed to `statistic`,
+/// so order based statistics such as `median` or `Quantile::trimean` can be used directly
+pub fn bootstrap<'a, F, R>(
+    input: &Input,
+    statistic: F,
+    n_resamples: usize,
+    rng: &mut R,
+) -> Bootstrap<'a>
+where
+    F: Fn(&Input) -> Num + 'a,
+    R: Rng,
+{
+    Bootstrap::new(
+        input.len(),
+        sorted_statistic(input, statistic),
+        n_resamples,
+        rng,
+    )
+}
+
+/// bootstrap of a statistic of paired observations, such as `correlation`.
+/// the pairs `(input1[i], input2[i])` are resampled together
+pub fn bootstrap_paired<'a, F, R>(
+    input1: &Input,
+    input2: &Input,
+    statistic: F,
+    n_resamples: usize,
+    rng: &mut R,
+) -> Bootstrap<'a>
+where
+    F: Fn(&Input, &Input) -> Num + 'a,
+    R: Rng,
+{
+    assert_eq!(
+        input1.len(),
+        input2.len(),
+        "paired samples must have the same length"
+    );
+    let (x, y) = (input1.to_vec(), input2.to_vec());
+    Bootstrap::new(
+        input1.len(),
+        move |indices: &[usize]| {
+            let x = indices.iter().map(|i| x[*i]).collect::<Vec<Num>>();
+            let y = indices.iter().map(|i| y[*i]).collect::<Vec<Num>>();
+            statistic(&x, &y)
+        },
+        n_resamples,
+        rng,
+    )
+}
+
+impl<'a> Bootstrap<'a> {
+    fn new<F, R>(size: usize, statistic: F, n_resamples: usize, rng: &mut R) -> Self
+    where
+        F: Fn(&[usize]) -> Num + 'a,
+        R: Rng,
+    {
+        let estimate = statistic(&(0..size).collect::<Vec<usize>>());
+        let mut members = Vec::with_capacity(n_resamples);
+        let replicates = (0..n_resamples)
+            .map(|_| {
+                let indices = draw(size, rng);
+                members.push(membership(&indices, size));
+                statistic(&indices)
+            })
+            .collect::<Vec<Num>>();
+        let standard_error = standard_deviation_sample(&replicates);
+        let bias = mean(&replicates) - estimate;
+
+        Bootstrap {
+            statistic: Box::new(statistic),
+            size,
+            estimate,
+            replicates,
+            standard_error,
+            bias,
+            members,
+        }
+    }
+
+    /// leave-one-out jackknife of the bootstrapped statistic
+    pub fn jackknife(&self) -> Jackknife {
+        delete_d(self.size, 1, &self.statistic)
+    }
+
+    /// jackknife-after-bootstrap: the bootstrap standard error recomputed without each observation
+    /// from the resamples that happen to leave it out, so no further resampling is needed
+    pub fn jackknife_after_bootstrap(&self) -> JackknifeAfterBootstrap {
+        let n = self.size as Num;
+        let standard_errors = (0..self.size)
+            .map(|i| {
+                let without = self
+                    .replicates
+                    .iter()
+                    .zip(self.members.iter())
+                    .filter(|(_, bits)| bits[i / 64] >> (i % 64) & 1 == 0)
+                    .map(|(r, _)| *r)
+                    .collect::<Vec<Num>>();
+                if without.len() < 2 {
+                    Num::NAN
+                } else {
+                    standard_deviation_sample(&without)
+                }
+            })
+            .collect::<Vec<Num>>();
+
+        let se_mean = mean(&standard_errors);
+        let squares = standard_errors
+            .iter()
+            .fold(0 as Num, |acc, s| acc + (s - se_mean).powi(2));
+
+        let jackknife = self.jackknife();
+        let jackknife_mean = mean(&jackknife.replicates);
+        JackknifeAfterBootstrap {
+            standard_error: ((n - 1.) / n * squares).sqrt(),
+            influence: jackknife
+                .replicates
+                .iter()
+                .map(|t| (n - 1.) * (jackknife_mean - t))
+                .collect(),
+            standard_errors,
+        }
+    }
+
+    /// the estimate minus its bootstrap estimate of bias
+    pub fn bias_corrected(&self) -> Num {
+        self.estimate - self.bias
+    }
+
+    /// interpolated quantiles of the replicates at `probabilities`
+    fn replicate_quantiles(&self, probabilities: (Num, Num)) -> (Num, Num) {
+        let sorted = sort_float(&self.replicates);
+        (
+            interpolated_quantile(&sorted, probabilities.0),
+            interpolated_quantile(&sorted, probabilities.1),
+        )
+    }
+
+    fn interval(&self, (lower, upper): (Num, Num), level: Num) -> Estimate {
+        Estimate {
+            value: self.estimate,
+            lower,
+            upper,
+            level,
+        }
+    }
+
+    /// quantiles of the replicates
+    pub fn percentile_interval(&self, level: Num) -> Estimate {
+        let alpha = 1. - level;
+        let bounds = self.replicate_quantiles((alpha / 2., 1. - alpha / 2.));
+        self.interval(bounds, level)
+    }
+
+    /// basic (reverse percentile) interval, the percentile interval reflected around the estimate
+    pub fn basic_interval(&self, level: Num) -> Estimate {
+        let alpha = 1. - level;
+        let (lower, upper) = self.replicate_quantiles((alpha / 2., 1. - alpha / 2.));
+        self.interval(
+            (2. * self.estimate - upper, 2. * self.estimate - lower),
+            level,
+        )
+    }
+
+    /// bias corrected and accelerated interval. the acceleration is estimated by the jackknife
+    pub fn bca_interval(&self, level: Num) -> Estimate {
+        let normal = Normal::standard();
+        let below = self.replicates.iter().fold(0 as Num, |acc, r| {
+            if *r < self.estimate {
+                acc + 1.
+            } else if *r == self.estimate {
+                acc + 0.5
+            } else {
+                acc
+            }
+        });
+        let bias_correction = normal.quantile(below / self.replicates.len() as Num);
+
+        let jackknife = self.jackknife().replicates;
+        let jackknife_mean = mean(&jackknife);
+        let (squares, cubes) = jackknife.iter().fold((0 as Num, 0 as Num), |acc, t| {
+            let d = jackknife_mean - t;
+            (acc.0 + d * d, acc.1 + d * d * d)
+        });
+        let acceleration = if squares > 0. {
+            cubes / (6. * squares.powf(1.5))
+        } else {
+            0.
+        };
+
+        let alpha = 1. - level;
+        let adjusted = |p: Num| {
+            let z = bias_correction + normal.quantile(p);
+            normal.cdf(bias_correction + z / (1. - acceleration * z))
+        };
+        let bounds = self.replicate_quantiles((adjusted(alpha / 2.), adjusted(1. - alpha / 2.)));
+        self.interval(bounds, level)
+    }
+
+    /// studentized (bootstrap-t) interval. every resample is studentized with a standard error
+    /// from `n_inner` resamples of its own, so it costs `n_inner` times the original bootstrap.
+    /// resamples whose standard error is zero are skipped
+    pub fn studentized_interval<R: Rng>(
+        &self,
+        level: Num,
+        n_inner: usize,
+        rng: &mut R,
+    ) -> Estimate {
+        let studentized = (0..self.replicates.len())
+            .filter_map(|_| {
+                let indices = draw(self.size, rng);
+                let replicate = (self.statistic)(&indices);
+                let inner = (0..n_inner)
+                    .map(|_| {
+                        let resample = draw(self.size, rng)
+                            .iter()
+                            .map(|i| indices[*i])
+                            .collect::<Vec<usize>>();
+                        (self.statistic)(&resample)
+                    })
+                    .collect::<Vec<Num>>();
+                let t = (replicate - self.estimate) / standard_deviation_sample(&inner);
+                if t.is_finite() {
+                    Some(t)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<Num>>();
+
+        let sorted = sort_float(&studentized);
+        let alpha = 1. - level;
+        let upper_t = interpolated_quantile(&sorted, 1. - alpha / 2.);
+        let lower_t = interpolated_quantile(&sorted, alpha / 2.);
+        self.interval(
+            (
+                self.estimate - upper_t * self.standard_error,
+                self.estimate - lower_t * self.standard_error,
+            ),
+            level,
+        )
+    }
+}
+
+/// p-value of `observed` among the statistics of the rearranged samples.
+/// a two sided p-value is twice the smaller one sided one.
+/// monte carlo p-values count the observed arrangement as one of the draws, so they are never zero
+fn permutation_p_value(
+    observed: Num,
+    permuted: &[Num],
+    exact: bool,
+    alternative: Alternative,
+) -> Num {
+    let tolerance = TOLERANCE * observed.abs().max(1.);
+    let above = permuted
+        .iter()
+        .filter(|t| **t >= observed - tolerance)
+        .count();
+    let below = permuted
+        .iter()
+        .filter(|t| **t <= observed + tolerance)
+        .count();
+    let offset = if exact { 0 } else { 1 };
+    let total = (permuted.len() + offset) as Num;
+    let greater = (above + offset) as Num / total;
+    let less = (below + offset) as Num / total;
+
+    match alternative {
+        Alternative::Greater => greater,
+        Alternative::Less => less,
+        Alternative::TwoSided => (2. * greater.min(less)).min(1.),
+    }
+}
+
+/// calls `visit` with every subset of `k` indices out of `0..n`, in lexicographic order
+fn for_each_combination<F: FnMut(&[usize])>(n: usize, k: usize, mut visit: F) {
+    let mut subset = (0..k).collect::<Vec<usize>>();
+    loop {
+        visit(&subset);
+        // rightmost index that has not reached its last possible value
+        match (0..k).rev().find(|i| subset[*i] != i + n - k) {
+            None => return,
+            Some(i) => {
+                subset[i] += 1;
+                for j in i + 1..k {
+                    subset[j] = subset[j - 1] + 1;
+                }
+            }
+        }
+    }
+}
+
+/// calls `visit` with every permutation of `0..n`, using heap's algorithm
+fn for_each_permutation<F: FnMut(&[usize])>(n: usize, mut visit: F) {
+    let mut permutation = (0..n).collect::<Vec<usize>>();
+    let mut counters = vec![0; n];
+    visit(&permutation);
+
+    let mut i = 1;
+    while i < n {
+        if counters[i] < i {
+            if i % 2 == 1 {
+                permutation.swap(counters[i], i);
+            } else {
+                permutation.swap(0, i);
+            }
+            visit(&permutation);
+            counters[i] += 1;
+            i = 1;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+/// two sample permutation test of `statistic(input1, input2)`, e.g. the difference of means.
+/// every split of the pooled sample is enumerated when there are at most `n_permutations` of them,
+/// otherwise `n_permutations` random splits are drawn
+pub fn permutation_test<F, R>(
+    input1: &Input,
+    input2: &Input,
+    statistic: F,
+    n_permutations: usize,
+    alternative: Alternative,
+    rng: &mut R,
+) -> TestResult
+where
+    F: Fn(&Input, &Input) -> Num,
+    R: Rng,
+{
+    let n1 = input1.len();
+    let n = n1 + input2.len();
+    let pooled = input1
+        .iter()
+        .chain(input2.iter())
+        .copied()
+        .collect::<Vec<Num>>();
+    let observed = statistic(input1, input2);
+    let exact = ln_choose(n as Num, n1 as Num).exp() <= n_permutations as Num + 0.5;
+
+    let permuted = if exact {
+        let mut permuted = Vec::new();
+        for_each_combination(n, n1, |first| {
+            let mut in_first = vec![false; n];
+            first.iter().for_each(|i| in_first[*i] = true);
+            let (mut a, mut b) = (Vec::with_capacity(n1), Vec::with_capacity(n - n1));
+            for (x, first) in pooled.iter().zip(in_first.iter()) {
+                if *first {
+                    a.push(*x);
+                } else {
+                    b.push(*x);
+                }
+            }
+            permuted.push(statistic(&a, &b));
+        });
+        permuted
+    } else {
+        let mut shuffled = pooled.clone();
+        (0..n_permutations)
+            .map(|_| {
+                shuffled.shuffle(rng);
+                statistic(&shuffled[..n1], &shuffled[n1..])
+            })
+            .collect()
+    };
+
+    TestResult {
+        statistic: observed,
+        df: None,
+        p_value: permutation_p_value(observed, &permuted, exact, alternative),
+    }
+}
+
+/// one sample sign flip test of `statistic`, e.g. the mean, applied to the differences `input - mu`.
+/// under the null hypothesis the differences are symmetric around zero so their signs are exchangeable.
+/// all sign patterns are enumerated when there are at most `n_permutations` of them
+pub fn sign_flip_test<F, R>(
+    input: &Input,
+    mu: Num,
+    statistic: F,
+    n_permutations: usize,
+    alternative: Alternative,
+    rng: &mut R,
+) -> TestResult
+where
+    F: Fn(&Input) -> Num,
+    R: Rng,
+{
+    let n = input.len();
+    let differences = input.iter().map(|x| x - mu).collect::<Vec<Num>>();
+    let observed = statistic(&differences);
+    let exact = n < 64 && (1u64 << n) <= n_permutations as u64;
+
+    let flip = |negative: &dyn Fn(usize) -> bool| {
+        differences
+            .iter()
+            .enumerate()
+            .map(|(i, d)| if negative(i) { -d } else { *d })
+            .collect::<Vec<Num>>()
+    };
+    let permuted = if exact {
+        (0..1u64 << n)
+            .map(|mask| statistic(&flip(&|i| mask >> i & 1 == 1)))
+            .collect::<Vec<Num>>()
+    } else {
+        (0..n_permutations)
+            .map(|_| {
+                let signs = (0..n).map(|_| rng.gen::<bool>()).collect::<Vec<bool>>();
+                statistic(&flip(&|i| signs[i]))
+            })
+            .collect()
+    };
+
+    TestResult {
+        statistic: observed,
+        df: None,
+        p_value: permutation_p_value(observed, &permuted, exact, alternative),
+    }
+}
+
+/// paired permutation test, the sign flip test of the differences `input1 - input2`
+pub fn paired_permutation_test<F, R>(
+    input1: &Input,
+    input2: &Input,
+    statistic: F,
+    n_permutations: usize,
+    alternative: Alternative,
+    rng: &mut R,
+) -> TestResult
+where
+    F: Fn(&Input) -> Num,
+    R: Rng,
+{
+    assert_eq!(
+        input1.len(),
+        input2.len(),
+        "paired samples must have the same length"
+    );
+    let differences = input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(x, y)| x - y)
+        .collect::<Vec<Num>>();
+    sign_flip_test(
+        &differences,
+        0.,
+        statistic,
+        n_permutations,
+        alternative,
+        rng,
+    )
+}
+
+/// permutation test of the pearson correlation against zero, which shuffles `input2`.
+/// every permutation is enumerated when there are at most `n_permutations` of them
+pub fn correlation_permutation_test<R: Rng>(
+    input1: &Input,
+    input2: &Input,
+    n_permutations: usize,
+    alternative: Alternative,
+    rng: &mut R,
+) -> TestResult {
+    let n = input1.len();
+    let observed = correlation(input1, input2);
+    let permutations = (1..=n).map(|i| i as Num).product::<Num>();
+    let exact = permutations <= n_permutations as Num + 0.5;
+
+    let permuted = if exact {
+        let mut permuted = Vec::new();
+        for_each_permutation(n, |order| {
+            let shuffled = order.iter().map(|i| input2[*i]).collect::<Vec<Num>>();
+            permuted.push(correlation(input1, &shuffled));
+        });
+        permuted
+    } else {
+        let mut shuffled = input2.to_vec();
+        (0..n_permutations)
+            .map(|_| {
+                shuffled.shuffle(rng);
+                correlation(input1, &shuffled)
+            })
+            .collect()
+    };
+
+    TestResult {
+        statistic: observed,
+        df: None,
+        p_value: permutation_p_value(observed, &permuted, exact, alternative),
+    }
+}
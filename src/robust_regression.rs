@@ -0,0 +1,247 @@
+//! regression lines that resist outliers. every estimator returns a `LinearRegression`
+use crate::bi_dimentional::{linear_regression, weighted_linear_regression, LinearRegression};
+use crate::generic_types::{Input, Num, OrdFloat};
+use crate::uni_dimentional::{median, sort_float};
+use rand::seq::index::sample;
+use rand::Rng;
+
+const MAX_ITERATION: usize = 200;
+
+/// draws of a random pair before giving up on finding two points with distinct x
+const MAX_DRAWS: usize = 1000;
+
+/// consistency constant that turns the median absolute residual into a standard deviation for normal errors
+const MAD_SCALE: Num = 0.6744897501960817;
+
+fn unsorted_median(input: &Input) -> Num {
+    median(&sort_float(input))
+}
+
+/// median of `y - gradient * x`
+fn median_intercept(input_x: &Input, input_y: &Input, gradient: Num) -> Num {
+    let offsets = input_x
+        .iter()
+        .zip(input_y.iter())
+        .map(|(x, y)| y - gradient * x)
+        .collect::<Vec<Num>>();
+    unsorted_median(&offsets)
+}
+
+/// theil–sen estimator. the gradient is the median of the slopes between every pair of points
+/// with distinct x, and the intercept the median of `y - gradient * x`.
+/// it tolerates up to about 29% of outliers
+pub fn theil_sen(input_x: &Input, input_y: &Input) -> LinearRegression {
+    let n = input_x.len();
+    let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n {
+        for j in i + 1..n {
+            if input_x[i] != input_x[j] {
+                slopes.push((input_y[j] - input_y[i]) / (input_x[j] - input_x[i]));
+            }
+        }
+    }
+    let gradient = unsorted_median(&slopes);
+    let intercept = median_intercept(input_x, input_y, gradient);
+    LinearRegression::new(intercept, gradient, input_x, input_y)
+}
+
+/// siegel's repeated medians. for every point the median of its slopes and intercepts to the others
+/// is taken, and the line is the median of those. it tolerates up to 50% of outliers
+pub fn siegel_repeated_medians(input_x: &Input, input_y: &Input) -> LinearRegression {
+    let n = input_x.len();
+    let (mut gradients, mut intercepts) = (Vec::with_capacity(n), Vec::with_capacity(n));
+    for i in 0..n {
+        let (mut slopes, mut offsets) = (Vec::with_capacity(n - 1), Vec::with_capacity(n - 1));
+        for j in (0..n).filter(|j| input_x[*j] != input_x[i]) {
+            let dx = input_x[j] - input_x[i];
+            slopes.push((input_y[j] - input_y[i]) / dx);
+            offsets.push((input_x[j] * input_y[i] - input_x[i] * input_y[j]) / dx);
+        }
+        if !slopes.is_empty() {
+            gradients.push(unsorted_median(&slopes));
+            intercepts.push(unsorted_median(&offsets));
+        }
+    }
+    LinearRegression::new(
+        unsorted_median(&intercepts),
+        unsorted_median(&gradients),
+        input_x,
+        input_y,
+    )
+}
+
+/// weight function of an m-estimator, applied to residuals divided by their robust scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MEstimator {
+    /// quadratic loss inside `k` and linear outside. `k = 1.345` gives 95% efficiency for normal errors
+    Huber { k: Num },
+    /// tukey's bisquare, which ignores residuals beyond `c`. `c = 4.685` gives 95% efficiency for normal errors
+    Bisquare { c: Num },
+}
+
+impl MEstimator {
+    fn weight(self, u: Num) -> Num {
+        match self {
+            MEstimator::Huber { k } => {
+                if u.abs() <= k {
+                    1.
+                } else {
+                    k / u.abs()
+                }
+            }
+            MEstimator::Bisquare { c } => {
+                if u.abs() < c {
+                    (1. - (u / c).powi(2)).powi(2)
+                } else {
+                    0.
+                }
+            }
+        }
+    }
+}
+
+/// m-estimation by iteratively reweighted least squares, starting from the least squares line.
+/// the residual scale is re-estimated every iteration as `median(|residuals|) / 0.6745`.
+/// `weights` of the returned model holds the final robustness weights
+pub fn m_estimator_regression(
+    input_x: &Input,
+    input_y: &Input,
+    estimator: MEstimator,
+) -> LinearRegression {
+    let mut model = linear_regression(input_x, input_y);
+    for _ in 0..MAX_ITERATION {
+        let absolute = model
+            .residuals
+            .iter()
+            .map(|r| r.abs())
+            .collect::<Vec<Num>>();
+        let scale = unsorted_median(&absolute) / MAD_SCALE;
+        if scale == 0. {
+            break;
+        }
+        let weights = model
+            .residuals
+            .iter()
+            .map(|r| estimator.weight(r / scale))
+            .collect::<Vec<Num>>();
+        let next = weighted_linear_regression(input_x, input_y, &weights);
+
+        let converged = (next.gradient - model.gradient).abs()
+            <= 1e-10 * (1. + model.gradient.abs())
+            && (next.intercept - model.intercept).abs() <= 1e-10 * (1. + model.intercept.abs());
+        model = next;
+        if converged {
+            break;
+        }
+    }
+    model
+}
+
+/// least squares line through the given points
+fn subset_regression(input_x: &Input, input_y: &Input, subset: &[usize]) -> LinearRegression {
+    let x = subset.iter().map(|i| input_x[*i]).collect::<Vec<Num>>();
+    let y = subset.iter().map(|i| input_y[*i]).collect::<Vec<Num>>();
+    let fit = linear_regression(&x, &y);
+    LinearRegression::new(fit.intercept, fit.gradient, input_x, input_y)
+}
+
+/// a random pair of points with distinct x, or `None` when `MAX_DRAWS` draws found none
+fn distinct_pair<R: Rng>(input_x: &Input, rng: &mut R) -> Option<Vec<usize>> {
+    (0..MAX_DRAWS)
+        .map(|_| sample(rng, input_x.len(), 2).into_vec())
+        .find(|pair| input_x[pair[0]] != input_x[pair[1]])
+}
+
+/// indices of the `coverage` smallest squared residuals and their sum
+fn smallest_residuals(model: &LinearRegression, coverage: usize) -> (Vec<usize>, Num) {
+    let squared = model.residuals.iter().map(|r| r * r).collect::<Vec<Num>>();
+    let mut order = (0..squared.len()).collect::<Vec<usize>>();
+    order.sort_by_key(|i| OrdFloat(squared[*i]));
+    order.truncate(coverage);
+    let objective = order.iter().map(|i| squared[*i]).sum();
+    (order, objective)
+}
+
+/// least trimmed squares, which minimizes the sum of the `coverage` smallest squared residuals.
+/// `coverage = (n + 3) / 2` gives the highest breakdown point.
+/// each of `n_starts` random pairs of points is refined by concentration steps until the objective stops decreasing.
+/// gradient and intercept are NaN when no pair of points with distinct x is found
+pub fn least_trimmed_squares<R: Rng>(
+    input_x: &Input,
+    input_y: &Input,
+    coverage: usize,
+    n_starts: usize,
+    rng: &mut R,
+) -> LinearRegression {
+    let mut best: Option<(LinearRegression, Num)> = None;
+    for _ in 0..n_starts {
+        let pair = match distinct_pair(input_x, rng) {
+            Some(pair) => pair,
+            None => break,
+        };
+        let start = subset_regression(input_x, input_y, &pair);
+        let (mut subset, mut objective) = smallest_residuals(&start, coverage);
+        for _ in 0..MAX_ITERATION {
+            let next = subset_regression(input_x, input_y, &subset);
+            let (next_subset, next_objective) = smallest_residuals(&next, coverage);
+            if next_objective >= objective {
+                break;
+            }
+            subset = next_subset;
+            objective = next_objective;
+        }
+        let model = subset_regression(input_x, input_y, &subset);
+        let (_, objective) = smallest_residuals(&model, coverage);
+        let better = match &best {
+            Some((_, b)) => objective < *b,
+            None => true,
+        };
+        if better {
+            best = Some((model, objective));
+        }
+    }
+    match best {
+        Some((model, _)) => model,
+        None => LinearRegression::new(Num::NAN, Num::NAN, input_x, input_y),
+    }
+}
+
+/// random sample consensus. lines through `n_iterations` random pairs of points are scored by how many points
+/// lie within `threshold` of them, ties broken by the smaller sum of squared inlier residuals.
+/// the returned line is the least squares fit to the inliers of the best one,
+/// or NaN when no pair of points with distinct x is found
+pub fn ransac<R: Rng>(
+    input_x: &Input,
+    input_y: &Input,
+    threshold: Num,
+    n_iterations: usize,
+    rng: &mut R,
+) -> LinearRegression {
+    let n = input_x.len();
+    let mut best: Option<(Vec<usize>, Num)> = None;
+    for _ in 0..n_iterations {
+        let pair = match distinct_pair(input_x, rng) {
+            Some(pair) => pair,
+            None => break,
+        };
+        let candidate = subset_regression(input_x, input_y, &pair);
+        let inliers = (0..n)
+            .filter(|i| candidate.residuals[*i].abs() <= threshold)
+            .collect::<Vec<usize>>();
+        let error = inliers
+            .iter()
+            .map(|i| candidate.residuals[*i].powi(2))
+            .sum();
+        let better = match &best {
+            Some((b, e)) => inliers.len() > b.len() || (inliers.len() == b.len() && error < *e),
+            None => true,
+        };
+        if better {
+            best = Some((inliers, error));
+        }
+    }
+    match best {
+        Some((inliers, _)) => subset_regression(input_x, input_y, &inliers),
+        None => LinearRegression::new(Num::NAN, Num::NAN, input_x, input_y),
+    }
+}
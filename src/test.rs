@@ -1,5 +1,5 @@
 fn test_value() -> Vec<f64> {
-    vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
         .iter()
         .map(|i| *i as f64)
         .collect::<Vec<f64>>()
@@ -40,9 +40,7 @@ pub fn variance() {
     let list = [600., 470., 170., 430., 300.];
     assert_eq!(crate::mean(&list), 394.);
     assert_eq!(crate::population_variance(&list), 21704.);
-    assert_eq!(crate::sample_variance(&list).round(), 165.);
-
-    assert_eq!(crate::sample_variance(&list).round(), 165.);
+    assert_eq!(crate::sample_variance(&list), 27130.);
 }
 
 #[test]
@@ -53,7 +51,23 @@ pub fn deviation() {
     assert_eq!(std.round(), 147.);
 
     let std2 = crate::standard_deviation_sample(&input[..]);
-    assert_eq!(std2.round(), 13.);
+    assert_eq!(std2.round(), 165.);
+}
+
+#[test]
+pub fn median() {
+    assert_eq!(crate::median(&[1., 2., 3., 4.]), 2.5);
+    assert_eq!(crate::median(&[1., 2., 3.]), 2.);
+    assert_eq!(crate::median_absolute_deviaiton(&[1., 2., 3., 4.]), 1.);
+    assert_eq!(crate::median_absolute_deviaiton(&[1., 2., 4., 7., 20.]), 3.);
+}
+
+#[test]
+pub fn entropy() {
+    let round = |x: f64| (x * 1e6).round();
+    // used to return zero for every input
+    assert_eq!(round(crate::entropy(&[1., 1., 1., 1.])), round(4f64.ln()));
+    assert_eq!(round(crate::entropy(&[2., 0., 2.])), round(2f64.ln()));
 }
 
 #[test]
@@ -68,3 +82,927 @@ pub fn generics() {
 
     assert_eq!(x, y);
 }
+
+#[test]
+pub fn distributions() {
+    let normal = crate::Normal::standard();
+    assert_eq!((normal.cdf(1.96) * 1e6).round(), 975002.);
+    assert_eq!((normal.quantile(0.975) * 1e6).round(), 1959964.);
+
+    let t = crate::StudentT::new(10.);
+    assert_eq!((t.cdf(2.228139) * 1e6).round(), 975000.);
+    assert_eq!((t.quantile(0.975) * 1e6).round(), 2228139.);
+}
+
+#[test]
+pub fn t_tests() {
+    use crate::Alternative;
+    let a = [5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.5];
+    let b = [4.2, 4.8, 4.4, 5.0, 4.6, 4.3];
+
+    let one = crate::one_sample_t_test(&a, 5., Alternative::TwoSided, 0.95);
+    assert_eq!(one.df, 6.);
+    assert_eq!((one.statistic * 1e4).round(), 31278.);
+    assert_eq!((one.p_value * 1e4).round(), 204.);
+
+    let pooled = crate::two_sample_t_test(&a, &b, Alternative::TwoSided, 0.95);
+    assert_eq!(pooled.df, 11.);
+    assert_eq!((pooled.statistic * 1e4).round(), 46166.);
+
+    let welch = crate::welch_t_test(&a, &b, Alternative::Greater, 0.95);
+    assert_eq!((welch.df * 1e3).round(), 10963.);
+    assert!(welch.p_value < 0.001);
+    assert_eq!(welch.confidence_interval.1, f64::INFINITY);
+
+    let paired = crate::paired_t_test(&a[..6], &b, Alternative::TwoSided, 0.95);
+    assert_eq!(paired.df, 5.);
+    assert!(paired.confidence_interval.0 < paired.mean_difference);
+}
+
+#[test]
+pub fn rank() {
+    assert_eq!(crate::rank(&[10., 20., 10., 40.], crate::TieMethod::Average), vec![1.5, 3., 1.5, 4.]);
+}
+
+#[test]
+pub fn tie_methods() {
+    use crate::{argsort, normal_scores, percentile_rank, rank, NormalScores, TieMethod};
+    let data = [10., 20., 10., 40., 20., 10.];
+    assert_eq!(argsort(&data), vec![0, 2, 5, 1, 4, 3]);
+    assert_eq!(rank(&data, TieMethod::Min), vec![1., 4., 1., 6., 4., 1.]);
+    assert_eq!(rank(&data, TieMethod::Max), vec![3., 5., 3., 6., 5., 3.]);
+    assert_eq!(rank(&data, TieMethod::Dense), vec![1., 2., 1., 3., 2., 1.]);
+    assert_eq!(rank(&data, TieMethod::Ordinal), vec![1., 4., 2., 6., 5., 3.]);
+
+    let random = rank(&data, TieMethod::Random { seed: 3 });
+    assert_eq!(random, rank(&data, TieMethod::Random { seed: 3 }));
+    assert_eq!(random[0] + random[2] + random[5], 6.);
+    assert_eq!(random[3], 6.);
+
+    assert_eq!(percentile_rank(&[1., 2., 3., 4.], 3.), 62.5);
+    let round = |x: f64| (x * 1e6).round();
+    let waerden = normal_scores(&[5., 1., 3.], NormalScores::VanDerWaerden);
+    assert_eq!(waerden.iter().map(|x| round(*x)).collect::<Vec<f64>>(), vec![674490., -674490., 0.]);
+    assert_eq!(round(normal_scores(&[5., 1., 3.], NormalScores::Blom)[1]), -869424.);
+}
+
+#[test]
+pub fn nonparametric_tests() {
+    use crate::Alternative;
+    let x = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+    let y = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.07, 3.15, 1.28];
+
+    let u = crate::mann_whitney_u(&x, &y, Alternative::TwoSided);
+    assert_eq!(u.statistic, 58.);
+    assert_eq!((u.p_value * 1e6).round(), 135911.);
+
+    let v = crate::wilcoxon_signed_rank_paired(&x, &y, Alternative::Greater);
+    assert_eq!(v.statistic, 40.);
+    assert_eq!(v.p_value, 0.01953125);
+
+    let s = crate::sign_test(&[1.2, -0.5, 3.1, 2.2, 0.7, 1.9, -0.1, 2.8, 0.4, 1.1], 0., Alternative::TwoSided);
+    assert_eq!(s.statistic, 8.);
+    assert_eq!((s.p_value * 1e6).round(), 109375.);
+
+    let h = crate::kruskal_wallis(&[
+        &[2.9, 3.0, 2.5, 2.6, 3.2],
+        &[3.8, 2.7, 4.0, 2.4],
+        &[2.8, 3.4, 3.7, 2.2, 2.0],
+    ]);
+    assert_eq!(h.df, Some(2.));
+    assert_eq!((h.statistic * 1e6).round(), 771429.);
+    assert_eq!((h.p_value * 1e6).round(), 679965.);
+
+    let q = crate::friedman(&[&[1., 2., 3.], &[2., 3., 1.], &[1., 3., 2.], &[1., 2., 3.], &[2., 3., 1.5]]);
+    assert_eq!((q.statistic * 1e6).round(), 3600000.);
+    assert_eq!((q.p_value * 1e6).round(), 165299.);
+}
+
+#[test]
+pub fn anova() {
+    let a = [4.2, 4.6, 3.9, 4.4, 4.8];
+    let b = [5.1, 5.6, 4.9, 5.3, 5.8];
+    let c = [4.0, 4.5, 4.1, 4.3, 3.8];
+    let groups: [&[f64]; 3] = [&a, &b, &c];
+
+    let table = crate::one_way_anova(&groups);
+    let line = table.row("groups").unwrap();
+    assert_eq!(line.df, 2.);
+    assert_eq!((line.f_statistic.unwrap() * 1e6).round(), 18439024.);
+    assert_eq!((line.p_value.unwrap() * 1e8).round(), 21898.);
+    assert_eq!((table.residuals().mean_square * 1e6).round(), 109333.);
+
+    let tukey = crate::tukey_hsd(&groups, 0.95);
+    assert_eq!(tukey.len(), 3);
+    assert_eq!((tukey[1].statistic * 1e6).round(), 1623005.);
+    assert_eq!((tukey[1].p_value * 1e4).round(), 5048.);
+    assert!(tukey[0].confidence_interval.1 < 0.);
+
+    let levene = crate::levene(&groups);
+    assert_eq!((levene.statistic * 1e6).round(), 304139.);
+    assert_eq!((levene.p_value * 1e6).round(), 743281.);
+
+    let bartlett = crate::bartlett(&groups);
+    assert_eq!((bartlett.statistic * 1e6).round(), 354148.);
+    assert_eq!((bartlett.p_value * 1e6).round(), 837718.);
+}
+
+#[test]
+pub fn two_way_anova() {
+    let input = [6., 7., 9., 10., 5., 6., 12., 13.];
+    let a = [0, 0, 0, 0, 1, 1, 1, 1];
+    let b = [0, 0, 1, 1, 0, 0, 1, 1];
+
+    let table = crate::two_way_anova(&input, &a, &b);
+    assert_eq!(table.row("a").unwrap().sum_of_squares, 2.);
+    assert_eq!(table.row("b").unwrap().sum_of_squares, 50.);
+    assert_eq!(table.row("a:b").unwrap().sum_of_squares, 8.);
+    assert_eq!(table.residuals().sum_of_squares, 2.);
+    assert_eq!(table.row("a:b").unwrap().f_statistic, Some(16.));
+
+    // unbalanced cells get type II sums of squares
+    let input = [6., 7., 9., 10., 5., 6., 12., 13., 8., 14., 11.];
+    let a = [0, 0, 0, 0, 1, 1, 1, 1, 0, 1, 1];
+    let b = [0, 0, 1, 1, 0, 0, 1, 1, 0, 1, 1];
+    let table = crate::two_way_anova(&input, &a, &b);
+    let round = |x: f64| (x * 1e6).round();
+    assert_eq!(round(table.row("a").unwrap().sum_of_squares), 1910526.);
+    assert_eq!(round(table.row("b").unwrap().sum_of_squares), 60043860.);
+    assert_eq!(round(table.row("a:b").unwrap().sum_of_squares), 12789474.);
+    assert_eq!(round(table.residuals().sum_of_squares), 8e6);
+    assert_eq!(table.residuals().df, 7.);
+    assert_eq!(round(table.row("a:b").unwrap().f_statistic.unwrap()), 11190789.);
+    // type II lines do not add up to the total, which eta squared divides by
+    assert_eq!(round(table.total_sum_of_squares), 93636364.);
+    assert_eq!(round(crate::eta_squared(&table, "a")), 20404.);
+
+    // a level without observations is left out
+    let skipped = a.iter().map(|l| l * 2).collect::<Vec<usize>>();
+    assert_eq!(crate::two_way_anova(&input, &skipped, &b), table);
+}
+
+#[test]
+pub fn contingency_table() {
+    let rows = ["a", "a", "b", "b", "a"];
+    let columns = [1, 2, 1, 1, 1];
+    let table = crate::ContingencyTable::new(&rows, &columns);
+    assert_eq!(table.observed(), &vec![vec![2., 1.], vec![2., 0.]]);
+    assert_eq!(table.row_totals(), vec![3., 2.]);
+    assert_eq!(table.expected()[0][0], 2.4);
+
+    let table = crate::ContingencyTable::from_counts(vec![vec![12., 5.], vec![3., 10.]]);
+    let yates = table.chi_square(true);
+    assert_eq!((yates.statistic * 1e6).round(), 4886878.);
+    assert_eq!((yates.p_value * 1e6).round(), 27062.);
+    assert_eq!((table.g_test().p_value * 1e6).round(), 8399.);
+    assert_eq!((table.cramers_v() * 1e6).round(), 470871.);
+
+    let fisher = table.fisher_exact_2x2(crate::Alternative::TwoSided);
+    assert_eq!((fisher.p_value * 1e6).round(), 25328.);
+    let greater = table.fisher_exact_2x2(crate::Alternative::Greater);
+    assert_eq!((greater.p_value * 1e6).round(), 12664.);
+    assert_eq!((table.fisher_exact().p_value * 1e6).round(), 25328.);
+
+    let table = crate::ContingencyTable::from_counts(vec![
+        vec![3., 1., 4.],
+        vec![2., 5., 1.],
+        vec![0., 3., 6.],
+    ]);
+    assert_eq!((table.fisher_exact().p_value * 1e6).round(), 50926.);
+
+    let table = crate::ContingencyTable::from_counts(vec![
+        vec![8., 4., 3., 2., 3.],
+        vec![4., 6., 3., 4., 3.],
+        vec![3., 3., 5., 4., 5.],
+        vec![2., 4., 4., 6., 4.],
+        vec![3., 3., 5., 4., 6.],
+    ]);
+    assert_eq!((table.fisher_exact().p_value * 1e6).round(), 846372.);
+
+    let paired = crate::ContingencyTable::from_counts(vec![vec![20., 8.], vec![2., 15.]]);
+    assert_eq!(paired.mcnemar(true).statistic, 2.5);
+
+    let strata = [
+        crate::ContingencyTable::from_counts(vec![vec![10., 5.], vec![4., 11.]]),
+        crate::ContingencyTable::from_counts(vec![vec![8., 6.], vec![5., 9.]]),
+    ];
+    let cmh = crate::cochran_mantel_haenszel(&strata, true);
+    assert_eq!((cmh.statistic * 1e6).round(), 4281979.);
+    assert_eq!((cmh.p_value * 1e6).round(), 38518.);
+    assert_eq!((crate::mantel_haenszel_odds_ratio(&strata) * 1e6).round(), 3589041.);
+}
+
+#[test]
+pub fn proportions() {
+    use crate::{Alternative, BinomialInterval};
+    let round = |(l, u): (f64, f64)| ((l * 1e6).round(), (u * 1e6).round());
+
+    let wilson = crate::binomial_confidence_interval(7, 20, 0.95, BinomialInterval::Wilson);
+    assert_eq!(round(wilson), (181192., 567146.));
+    let agresti = crate::binomial_confidence_interval(7, 20, 0.95, BinomialInterval::AgrestiCoull);
+    assert_eq!(round(agresti), (179926., 568411.));
+    let exact = crate::binomial_confidence_interval(7, 20, 0.95, BinomialInterval::ClopperPearson);
+    assert_eq!(round(exact), (153909., 592189.));
+    let jeffreys = crate::binomial_confidence_interval(7, 20, 0.95, BinomialInterval::Jeffreys);
+    assert_eq!(round(jeffreys), (172276., 567766.));
+
+    let binomial = crate::binomial_test(7, 20, 0.5, Alternative::TwoSided, 0.95);
+    assert_eq!((binomial.p_value * 1e6).round(), 263176.);
+    assert_eq!(round(binomial.confidence_interval), round(exact));
+
+    let two = crate::two_proportion_z_test(45, 100, 30, 100, Alternative::TwoSided, 0.95);
+    assert_eq!((two.statistic * 1e6).round(), 2190890.);
+    assert_eq!((two.p_value * 1e6).round(), 28460.);
+    assert_eq!(round(crate::newcombe_interval(45, 100, 30, 100, 0.95)), (15852., 276831.));
+}
+
+#[test]
+pub fn p_value_adjustment() {
+    use crate::Method;
+    let p = [0.01, 0.04, 0.03, 0.005, 0.2, 0.02];
+    let adjust = |method| {
+        crate::adjust_p_values(&p, method)
+            .iter()
+            .map(|q| (q * 1e6).round() / 1e6)
+            .collect::<Vec<f64>>()
+    };
+
+    assert_eq!(adjust(Method::Bonferroni), vec![0.06, 0.24, 0.18, 0.03, 1., 0.12]);
+    assert_eq!(adjust(Method::Holm), vec![0.05, 0.09, 0.09, 0.03, 0.2, 0.08]);
+    assert_eq!(adjust(Method::Hochberg), vec![0.05, 0.08, 0.08, 0.03, 0.2, 0.08]);
+    assert_eq!(adjust(Method::Hommel), vec![0.05, 0.08, 0.06, 0.03, 0.2, 0.06]);
+    assert_eq!(adjust(Method::BenjaminiHochberg), vec![0.03, 0.048, 0.045, 0.03, 0.2, 0.04]);
+
+    assert_eq!((crate::storey_pi0(&p, 0.1) * 1e6).round(), 185185.);
+    assert_eq!((crate::q_values(&p, 0.1)[4] * 1e6).round(), 37037.);
+}
+
+#[test]
+pub fn confidence_intervals() {
+    let round = |e: crate::Estimate| ((e.lower * 1e6).round(), (e.upper * 1e6).round());
+    let a = [5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.5];
+
+    assert_eq!(round(crate::mean_confidence_interval(&a, 0.95)), (5099521., 5814765.));
+    assert_eq!(round(crate::variance_confidence_interval(&a, 0.95)), (62089., 725055.));
+
+    let sorted = (1..=20).map(|i| i as f64).collect::<Vec<f64>>();
+    let median = crate::median_confidence_interval(&sorted, 0.95);
+    assert_eq!((median.value, median.lower, median.upper), (10.5, 6., 15.));
+
+    let x = [1., 2., 3., 4., 5., 6., 7., 8.];
+    let y = [2.1, 3.9, 6.2, 7.8, 10.1, 12.2, 13.8, 16.1];
+    let r = crate::correlation_confidence_interval(&x, &y, 0.95);
+    assert_eq!(round(r), (996654., 999899.));
+
+    let (intercept, gradient) = crate::regression_confidence_intervals(&x, &y, 0.95);
+    assert_eq!(round(gradient), (1929594., 2065644.));
+    assert_eq!((intercept.lower * 1e6).round(), -307796.);
+    assert!(gradient.contains(2.));
+}
+
+#[test]
+pub fn effect_sizes() {
+    let round = |x: f64| (x * 1e6).round();
+    let a = [5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.5];
+    let b = [4.2, 4.8, 4.4, 5.0, 4.6, 4.3];
+
+    let d = crate::cohens_d(&a, &b, 0.95);
+    assert_eq!((round(d.value), round(d.lower), round(d.upper)), (2568457., 1097500., 4039415.));
+    assert_eq!(round(crate::hedges_g(&a, &b, 0.95).value), 2389263.);
+    assert_eq!(round(crate::glass_delta(&a, &b)), 2943160.);
+    assert_eq!(round(crate::cliffs_delta(&a, &b)), 952381.);
+    assert_eq!(crate::rank_biserial(&a, &b), crate::cliffs_delta(&a, &b));
+
+    let table = crate::one_way_anova(&[
+        &[4.2, 4.6, 3.9, 4.4, 4.8],
+        &[5.1, 5.6, 4.9, 5.3, 5.8],
+        &[4.0, 4.5, 4.1, 4.3, 3.8],
+    ]);
+    assert_eq!(round(crate::eta_squared(&table, "groups")), 754491.);
+    assert_eq!(round(crate::omega_squared(&table, "groups")), 699267.);
+
+    let table = crate::ContingencyTable::from_counts(vec![vec![12., 5.], vec![3., 10.]]);
+    let odds = crate::odds_ratio(&table, 0.95);
+    assert_eq!((odds.value, round(odds.lower), round(odds.upper)), (8., 1522283., 42042118.));
+    assert_eq!(round(crate::relative_risk(&table, 0.95).lower), 1082434.);
+    assert_eq!(round(crate::number_needed_to_treat(&table)), -2104762.);
+}
+
+#[test]
+pub fn power_analysis() {
+    use crate::{Alternative, PowerAnalysis, PowerTest, TTestKind};
+    let round = |x: f64| (x * 1e5).round();
+    let t = PowerTest::T {
+        kind: TTestKind::TwoSample,
+        alternative: Alternative::TwoSided,
+    };
+
+    assert_eq!(round(crate::power(t, 0.5, 20., 0.05)), 33794.);
+    let n = PowerAnalysis {
+        test: t,
+        effect_size: Some(0.5),
+        n: None,
+        alpha: Some(0.05),
+        power: Some(0.8),
+    }
+    .solve();
+    assert_eq!(round(n.n.unwrap()), 6376561.);
+
+    let effect = PowerAnalysis {
+        effect_size: None,
+        n: Some(20.),
+        power: Some(0.33793903),
+        ..n
+    }
+    .solve();
+    assert_eq!(round(effect.effect_size.unwrap()), 50000.);
+
+    let anova = crate::power(PowerTest::Anova { groups: 4 }, 0.25, 20., 0.05);
+    assert_eq!(round(anova), 42039.);
+    let chi = crate::power(PowerTest::ChiSquare { df: 1 }, 0.3, 100., 0.05);
+    assert_eq!(round(chi), 85084.);
+    let correlation = PowerTest::Correlation {
+        alternative: Alternative::TwoSided,
+    };
+    assert_eq!(round(crate::power(correlation, 0.3, 50., 0.05)), 56437.);
+}
+
+#[test]
+pub fn bootstrap() {
+    use crate::{bootstrap, bootstrap_paired, correlation, median, Quantile};
+    use rand::{rngs::StdRng, SeedableRng};
+    let data = [
+        12.1, 9.8, 11.4, 10.2, 13.7, 8.9, 10.8, 12.6, 11.1, 9.5, 14.2, 10.4, 11.9, 10.0, 12.3,
+    ];
+
+    let first = bootstrap(&data, median, 500, &mut StdRng::seed_from_u64(7));
+    let second = bootstrap(&data, median, 500, &mut StdRng::seed_from_u64(7));
+    assert_eq!(first.replicates, second.replicates);
+    assert_eq!(first.estimate, 11.1);
+
+    let percentile = first.percentile_interval(0.95);
+    let basic = first.basic_interval(0.95);
+    assert!(percentile.contains(11.1) && first.bca_interval(0.95).contains(11.1));
+    assert_eq!(basic.lower, 2. * 11.1 - percentile.upper);
+
+    // the standard error of the mean is close to s / sqrt(n)
+    let mean = bootstrap(&data, crate::mean, 2000, &mut StdRng::seed_from_u64(1));
+    let expected = crate::standard_deviation_sample(&data) / (data.len() as f64).sqrt();
+    assert!((mean.standard_error / expected - 1.).abs() < 0.1);
+    let studentized = mean.studentized_interval(0.9, 25, &mut StdRng::seed_from_u64(2));
+    assert!(studentized.contains(mean.estimate));
+
+    let trimean = bootstrap(&data, |x| Quantile::new(x).trimean(), 200, &mut StdRng::seed_from_u64(3));
+    assert_eq!(trimean.estimate, Quantile::new(&crate::sort_float(&data)).trimean());
+
+    let y = data.iter().enumerate().map(|(i, x)| x + (i % 3) as f64).collect::<Vec<f64>>();
+    let paired = bootstrap_paired(&data, &y, correlation, 500, &mut StdRng::seed_from_u64(4));
+    assert_eq!(paired.estimate, correlation(&data, &y));
+    assert!(paired.bca_interval(0.95).upper <= 1.);
+}
+
+#[test]
+pub fn permutation_tests() {
+    use crate::{correlation_permutation_test, paired_permutation_test, permutation_test, Alternative};
+    use rand::{rngs::StdRng, SeedableRng};
+    let round = |x: f64| (x * 1e6).round();
+    let rng = &mut StdRng::seed_from_u64(11);
+    let difference = |a: &[f64], b: &[f64]| crate::mean(a) - crate::mean(b);
+
+    // 20 splits, only one of them puts all three smallest values in the first group
+    let exact = permutation_test(&[1., 2., 3.], &[4., 5., 6.], difference, 1000, Alternative::Less, rng);
+    assert_eq!(round(exact.p_value), 50000.);
+    let two_sided = permutation_test(&[1., 2., 3.], &[4., 5., 6.], difference, 1000, Alternative::TwoSided, rng);
+    assert_eq!(round(two_sided.p_value), 100000.);
+
+    let paired = paired_permutation_test(&[3., 4., 5., 6.], &[2., 2., 2., 2.], crate::mean, 100, Alternative::Greater, rng);
+    assert_eq!(paired.p_value, 1. / 16.);
+
+    let x = [1., 2., 3., 4., 5.];
+    let y = [2., 1., 4., 3., 5.];
+    let correlation = correlation_permutation_test(&x, &y, 1000, Alternative::Greater, rng);
+    assert_eq!(round(correlation.p_value), 66667.);
+    let sampled = correlation_permutation_test(&x, &y, 100, Alternative::Greater, &mut StdRng::seed_from_u64(5));
+    let again = correlation_permutation_test(&x, &y, 100, Alternative::Greater, &mut StdRng::seed_from_u64(5));
+    assert_eq!(sampled, again);
+    assert!(sampled.p_value > 0.01 && sampled.p_value < 0.2);
+}
+
+#[test]
+pub fn jackknife() {
+    use crate::{bootstrap, delete_d_jackknife, entropy, harmonic_mean, jackknife, mean};
+    use rand::{rngs::StdRng, SeedableRng};
+    let round = |x: f64| (x * 1e6).round();
+    let data = [2., 4., 5., 8., 3., 6.];
+    assert_eq!(round(entropy(&[1., 1., 2.])), 1039721.);
+
+    let harmonic = jackknife(&data, harmonic_mean);
+    assert_eq!(round(harmonic.bias), 191155.);
+    assert_eq!(round(harmonic.standard_error), 901753.);
+    let information = jackknife(&data, entropy);
+    assert_eq!(round(information.bias_corrected()), 2596370.);
+    assert_eq!(round(information.standard_error), 46959.);
+
+    // the jackknife standard error of the mean is exactly s / sqrt(n)
+    let linear = delete_d_jackknife(&data, mean, 2);
+    assert_eq!(round(linear.standard_error), 881917.);
+    assert_eq!(round(linear.bias), 0.);
+
+    let resampled = bootstrap(&data, mean, 300, &mut StdRng::seed_from_u64(9));
+    let diagnostics = resampled.jackknife_after_bootstrap();
+    assert_eq!(diagnostics.standard_errors.len(), 6);
+    assert!(diagnostics.standard_error > 0. && diagnostics.standard_error < resampled.standard_error);
+    // the influence of each observation on the mean is its deviation from the mean
+    assert_eq!(round(diagnostics.influence[3]), round(8. - 14. / 3.));
+}
+
+#[test]
+pub fn rank_correlation() {
+    use crate::{kendall_tau, kendall_test, spearman_test, Alternative, KendallVariant};
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 2., 3., 4., 5., 5., 5., 6., 7., 8., 9.];
+    let y = [2., 1., 3., 3., 5., 4., 6., 6., 8., 7., 9., 7.];
+
+    let spearman = spearman_test(&x, &y, Alternative::TwoSided, 0.95);
+    assert_eq!(round(spearman.coefficient), 923765.);
+    assert_eq!(round(spearman.p_value * 1e3), 17825.);
+    assert_eq!(round(spearman.confidence_interval.0), 735898.);
+    assert_eq!(round(spearman.confidence_interval.1), 979567.);
+
+    assert_eq!(round(kendall_tau(&x, &y, KendallVariant::A)), 757576.);
+    assert_eq!(round(kendall_tau(&x, &y, KendallVariant::C)), 781250.);
+    let kendall = kendall_test(&x, &y, KendallVariant::B, Alternative::TwoSided, 0.95);
+    assert_eq!(round(kendall.coefficient), 800026.);
+    assert_eq!(round(kendall.statistic), 3490604.);
+    assert_eq!(round(kendall.p_value), 482.);
+    assert_eq!(round(kendall.confidence_interval.0), 565308.);
+    assert_eq!(round(kendall.confidence_interval.1), 914895.);
+}
+
+#[test]
+pub fn correlation_matrices() {
+    use crate::{
+        correlation_matrix, covariance_matrix, ledoit_wolf, partial_correlation_matrix,
+        semi_partial_correlation_matrix, CorrelationMethod, Matrix, MissingValues,
+    };
+    let round = |x: f64| (x * 1e6).round();
+    let a = [1., 2., 3., 4., 5., 6., 7., 8.];
+    let b = [2., 1., 4., 3., 6., 5., 8., 9.];
+    let c = [5., 3., 4., 1., 2., f64::NAN, 0., 1.];
+    let d = [1., 4., 2., 5., 3., 6., 4., 8.];
+    let columns: [&[f64]; 4] = [&a, &b, &c, &d];
+
+    let pairwise = covariance_matrix(&columns, MissingValues::Pairwise);
+    assert_eq!(round(pairwise[(0, 2)]), -3928571.);
+    assert_eq!(round(pairwise[(0, 1)]), 6428571.);
+    let listwise = covariance_matrix(&columns, MissingValues::Listwise);
+    assert_eq!(round(listwise[(1, 0)]), 7428571.);
+
+    let spearman = correlation_matrix(&columns, CorrelationMethod::Spearman, MissingValues::Pairwise);
+    assert_eq!(round(spearman[(0, 1)]), round(crate::spearman_rho(&a, &b)));
+    assert_eq!(spearman[(3, 3)], 1.);
+
+    let partial = partial_correlation_matrix(&columns, CorrelationMethod::Pearson, MissingValues::Listwise);
+    assert_eq!(round(partial[(0, 1)]), 997244.);
+    assert_eq!(round(partial[(3, 2)]), 900937.);
+    let semi = semi_partial_correlation_matrix(&columns, CorrelationMethod::Pearson, MissingValues::Listwise);
+    assert_eq!(round(semi[(0, 1)]), 483491.);
+    assert_eq!(round(semi[(1, 0)]), 718987.);
+
+    let (shrunk, shrinkage) = ledoit_wolf(&columns);
+    assert_eq!(round(shrinkage), 263586.);
+    assert_eq!(round(shrunk[(0, 0)]), 5514308.);
+    assert_eq!(round(shrunk[(0, 1)]), 4689002.);
+
+    let m = Matrix::from_rows(&[&[4., 7.], &[2., 6.]]);
+    let identity = m.multiply(&m.inverse().unwrap());
+    assert_eq!(round(identity[(0, 0)]), 1e6);
+    assert_eq!(round(identity[(1, 0)]), 0.);
+    assert!(Matrix::from_rows(&[&[1., 2.], &[2., 4.]]).inverse().is_none());
+}
+
+#[test]
+pub fn regression_models() {
+    use crate::linear_regression;
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 3., 4., 5.];
+    let y = [2.1, 3.9, 6.2, 7.8, 10.1];
+
+    let model = linear_regression(&x, &y);
+    assert_eq!(round(model.gradient), 1990000.);
+    assert_eq!(round(model.intercept), 50000.);
+    assert_eq!(round(model.predict(10.)), 19950000.);
+    assert_eq!(model.predict_many(&x), model.fitted_values);
+    assert_eq!(round(model.residuals[2]), 180000.);
+    // the model keeps working after the training data is gone
+    let stored = {
+        let (x, y) = (x.to_vec(), y.to_vec());
+        linear_regression(&x, &y)
+    };
+    assert_eq!(stored, model);
+}
+
+#[test]
+pub fn regression_summary() {
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 3., 4., 5., 6., 7., 8.];
+    let y = [1.8, 4.1, 5.9, 8.3, 9.7, 12.4, 13.8, 16.9];
+    let summary = crate::linear_regression(&x, &y).summary(&x, 0.95);
+
+    let (intercept, gradient) = (&summary.coefficients[0], &summary.coefficients[1]);
+    assert_eq!(round(gradient.standard_error), 58033.);
+    assert_eq!(round(gradient.t_statistic), 35919681.);
+    assert_eq!(round(intercept.standard_error), 293052.);
+    assert_eq!(round(intercept.p_value), 395947.);
+    assert_eq!(round(summary.r_squared), 995371.);
+    assert_eq!(round(summary.adjusted_r_squared), 994600.);
+    assert_eq!(round(summary.residual_standard_error), 376096.);
+    assert_eq!(round(summary.f_test.statistic), 1290223453.);
+    assert_eq!(round(summary.aic), 10754998.);
+    assert_eq!(round(summary.bic), 10993323.);
+    assert_eq!(round(summary.durbin_watson), 3032976.);
+    assert_eq!(round(summary.leverage[0]), 416667.);
+    assert_eq!(round(summary.cooks_distance[7]), 1046330.);
+    assert_eq!(round(summary.studentized_residuals[7]), 2184286.);
+
+    let mean = summary.confidence_band(&[10.]);
+    assert_eq!((round(mean.lower), round(mean.upper)), (19731310., 21423452.));
+    let new = summary.prediction_band(&[10.]);
+    assert_eq!((round(new.lower), round(new.upper)), (19327284., 21827478.));
+}
+
+#[test]
+pub fn multiple_regression() {
+    use crate::{multiple_regression, Matrix};
+    let round = |x: f64| (x * 1e6).round();
+    let x1 = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+    let x2 = [2., 1., 4., 3., 6., 5., 8., 9., 7., 10.];
+    let y = [3.1, 3.9, 7.2, 7.8, 11.1, 11.0, 14.9, 16.8, 15.2, 19.9];
+
+    let model = multiple_regression(&[&x1, &x2], &y, true);
+    let coefficients = model.coefficients.iter().map(|c| round(*c)).collect::<Vec<f64>>();
+    assert_eq!(coefficients, vec![729937., 941824., 941824.]);
+    assert_eq!(round(model.predict(&[4.5, 5.])), 9677264.);
+
+    let summary = model.summary(&[&x1, &x2], 0.95);
+    assert_eq!(round(summary.coefficients[0].standard_error), 268819.);
+    assert_eq!(round(summary.coefficients[2].standard_error), 114042.);
+    assert_eq!(round(summary.r_squared), 996282.);
+    assert_eq!(round(summary.f_test.statistic), 937805058.);
+    assert_eq!(round(summary.variance_inflation_factors[1]), 7134434.);
+    assert_eq!(round(summary.condition_number), 19359098.);
+
+    // agrees with the single predictor fit
+    let simple = multiple_regression(&[&x1], &y, true);
+    let line = crate::linear_regression(&x1, &y);
+    assert_eq!(round(simple.coefficients[1]), round(line.gradient));
+
+    let (q, r) = Matrix::from_rows(&[&[3., 1.], &[4., 2.], &[0., 2.]]).qr();
+    let product = q.multiply(&r);
+    assert_eq!((round(product[(1, 0)]), round(product[(2, 1)])), (4e6, 2e6));
+    assert_eq!(round(r[(1, 0)]), 0.);
+}
+
+#[test]
+pub fn polynomial_regression() {
+    use crate::{polynomial_regression, select_polynomial_degree, DegreeSelection};
+    let round = |x: f64| (x * 1e6).round();
+    let x = (0..20).map(|i| i as f64 / 2.).collect::<Vec<f64>>();
+    let cubic = x.iter().map(|x| 1. - 2. * x + 0.5 * x.powi(3)).collect::<Vec<f64>>();
+
+    let model = polynomial_regression(&x, &cubic, 3);
+    let coefficients = model.coefficients.iter().map(|c| round(*c)).collect::<Vec<f64>>();
+    assert_eq!(coefficients, vec![1e6, -2e6, 0., 5e5]);
+    assert_eq!(round(model.predict(7.)), round(1. - 14. + 171.5));
+    assert_eq!(round(model.derivative(2., 1)), 4e6);
+    assert_eq!(round(model.derivative(2., 2)), 6e6);
+    assert_eq!(round(model.derivative(2., 4)), 0.);
+    assert_eq!(round(model.r_squared()), 1e6);
+
+    let noise = [
+        -0.3, 0., -0.1, 0.1, 0.1, -0.4, -0.5, 0.3, -0.2, -0.3, 0.5, 0., 0.3, 0., 0.1, -0.3, 0.1, 0.4,
+        0., 0.2,
+    ];
+    let noisy = cubic.iter().zip(noise.iter()).map(|(y, e)| y + e).collect::<Vec<f64>>();
+    assert_eq!(select_polynomial_degree(&x, &noisy, 6, DegreeSelection::Aic), 3);
+    let folds = DegreeSelection::CrossValidation { folds: 4 };
+    assert_eq!(select_polynomial_degree(&x, &noisy, 6, folds), 3);
+
+    let fit = polynomial_regression(&x, &noisy, 3);
+    let summary = fit.summary(&x, 0.95);
+    assert_eq!(round(summary.r_squared), round(fit.r_squared()));
+    assert!(summary.coefficients[3].confidence_interval.0 < 0.5);
+    assert!(summary.coefficients[3].confidence_interval.1 > 0.5);
+    // the covariance carried over from the orthogonal basis matches a fit on the powers themselves
+    let powers = (1..=3)
+        .map(|k| x.iter().map(|x| x.powi(k)).collect())
+        .collect::<Vec<Vec<f64>>>();
+    let powers = powers.iter().map(|p| &p[..]).collect::<Vec<&[f64]>>();
+    let raw = crate::multiple_regression(&powers, &noisy, true).summary(&powers, 0.95);
+    for (a, b) in summary.coefficients.iter().zip(raw.coefficients.iter()) {
+        assert_eq!(round(a.standard_error), round(b.standard_error));
+    }
+    for (a, b) in summary
+        .variance_inflation_factors
+        .iter()
+        .zip(raw.variance_inflation_factors.iter())
+    {
+        assert_eq!(round(*a), round(*b));
+    }
+
+    // with 3 folds of 6 points every training set has 4 distinct x, too few for degree 4
+    let short = [1., 2.2, 4.8, 9.1, 16.3, 24.7];
+    let folds = DegreeSelection::CrossValidation { folds: 3 };
+    assert_eq!(select_polynomial_degree(&x[..6], &short, 4, folds), 2);
+}
+
+#[test]
+pub fn curve_fitting() {
+    use crate::{
+        exponential_regression, logarithm_regression, logistic_growth_regression, power_regression,
+        saturating_regression,
+    };
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 3., 4., 5., 6.];
+
+    let exponential = exponential_regression(&x, &[2.9, 3.6, 5.1, 6.6, 9.2, 12.1]);
+    assert_eq!(round(exponential.a), 2085981.);
+    assert_eq!(round(exponential.b), 293458.);
+    assert_eq!(round(exponential.r_squared()), 998643.);
+    let exact = x.iter().map(|x| 2. * (0.3 * x).exp()).collect::<Vec<f64>>();
+    let exponential = exponential_regression(&x, &exact);
+    assert_eq!((round(exponential.a), round(exponential.b)), (2e6, 3e5));
+    assert_eq!(round(exponential.predict(10.)), round(2. * 3f64.exp()));
+
+    let power = power_regression(&x, &x.iter().map(|x| 3. * x.powf(1.5)).collect::<Vec<f64>>());
+    assert_eq!((round(power.a), round(power.b)), (3e6, 15e5));
+    assert_eq!(round(power.r_squared()), 1e6);
+
+    let e = std::f64::consts::E;
+    let logarithmic = logarithm_regression(&[1., e, e * e], &[3., 5., 7.]);
+    assert_eq!((round(logarithmic.a), round(logarithmic.b)), (3e6, 2e6));
+    assert_eq!(round(logarithmic.predict(e.powi(3))), 9e6);
+
+    let saturating = saturating_regression(&x, &x.iter().map(|x| 5. * x / (2. + x)).collect::<Vec<f64>>());
+    assert_eq!((round(saturating.a), round(saturating.b)), (5e6, 2e6));
+
+    let time = (0..=10).map(|t| t as f64).collect::<Vec<f64>>();
+    let growth = time.iter().map(|t| 10. / (1. + (-0.8 * (t - 5.)).exp())).collect::<Vec<f64>>();
+    let logistic = logistic_growth_regression(&time, &growth);
+    assert_eq!(round(logistic.capacity), 10e6);
+    assert_eq!(round(logistic.rate), 8e5);
+    assert_eq!(round(logistic.midpoint), 5e6);
+    assert_eq!(round(logistic.predict(5.)), 5e6);
+}
+
+#[test]
+pub fn weighted_and_robust_regression() {
+    use crate::{
+        ar1_regression, breusch_pagan, multiple_regression, weighted_linear_regression,
+        weighted_multiple_regression, white_test, RobustCovariance,
+    };
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+    let x2 = [3., 1., 4., 1., 5., 9., 2., 6., 5., 3.];
+    let y = [2.3, 4.1, 6.8, 7.2, 11.5, 12.1, 13.0, 17.9, 18.2, 22.4];
+    let weights = [1., 2., 1., 0.5, 1., 2., 1., 0.5, 1., 2.];
+
+    let weighted = weighted_linear_regression(&x, &y, &weights);
+    assert_eq!(round(weighted.intercept), -299361.);
+    assert_eq!(round(weighted.gradient), 2166304.);
+    assert_eq!(round(weighted.summary(&x, 0.95).coefficients[1].standard_error), 105558.);
+    let multiple = weighted_multiple_regression(&[&x], &y, &weights, true);
+    assert_eq!(round(multiple.coefficients[1]), 2166304.);
+
+    let ols = multiple_regression(&[&x, &x2], &y, true);
+    let se = |kind| {
+        ols.robust_coefficients(&[&x, &x2], kind, 0.95)
+            .iter()
+            .map(|c| round(c.standard_error))
+            .collect::<Vec<f64>>()
+    };
+    assert_eq!(se(RobustCovariance::HC0), vec![488558., 119674., 151978.]);
+    assert_eq!(se(RobustCovariance::HC3), vec![780314., 200439., 309581.]);
+    assert_eq!(se(RobustCovariance::NeweyWest { lags: 2 }), vec![314895., 97900., 96523.]);
+
+    let bp = breusch_pagan(&[&x, &x2], &ols.residuals);
+    assert_eq!((round(bp.statistic), round(bp.p_value)), (5734770., 56847.));
+    let white = white_test(&[&x, &x2], &ols.residuals);
+    assert_eq!((round(white.statistic), round(white.p_value)), (9195339., 101522.));
+
+    let (ar1, rho) = ar1_regression(&[&x], &y, true);
+    assert_eq!(round(ar1.coefficients[0]), -140450.);
+    assert_eq!(round(ar1.coefficients[1]), 2112586.);
+    assert_eq!(round(rho), -691408.);
+
+    // strongly alternating errors drive the estimate towards -1
+    let zigzag = [1., 3., 2., 5., 4., 7., 6., 9.];
+    let (ar1, rho) = ar1_regression(&[&x[..8]], &zigzag, true);
+    assert!(rho.abs() < 1.);
+    assert!(ar1.coefficients.iter().all(|b| b.is_finite()));
+}
+
+#[test]
+pub fn robust_regression() {
+    use crate::{
+        least_trimmed_squares, m_estimator_regression, ransac, siegel_repeated_medians, theil_sen,
+        MEstimator,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    let round = |x: f64| (x * 1e6).round();
+    let x = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+    let y = [2.1, 3.9, 6.2, 8.1, 9.8, 12.2, 30.0, 15.9, 18.1, 20.2];
+
+    let fit = theil_sen(&x, &y);
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (72222., 2011111.));
+    let fit = siegel_repeated_medians(&x, &y);
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (90000., 2008333.));
+    let fit = m_estimator_regression(&x, &y, MEstimator::Huber { k: 1.345 });
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (25143., 2011601.));
+    let fit = m_estimator_regression(&x, &y, MEstimator::Bisquare { c: 4.685 });
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (23567., 2006763.));
+
+    let mut x = (0..10).map(|x| x as f64).collect::<Vec<f64>>();
+    let mut y = x.iter().map(|x| 1. + 2. * x).collect::<Vec<f64>>();
+    x.extend_from_slice(&[2., 5., 8.]);
+    y.extend_from_slice(&[40., -20., 60.]);
+    let mut rng = StdRng::seed_from_u64(7);
+    let fit = least_trimmed_squares(&x, &y, (x.len() + 3) / 2, 100, &mut rng);
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (1e6, 2e6));
+    let fit = ransac(&x, &y, 0.5, 100, &mut rng);
+    assert_eq!((round(fit.intercept), round(fit.gradient)), (1e6, 2e6));
+    assert_eq!(round(fit.residuals[10]), 35e6);
+
+    let tied = [3., 3., 3., 3.];
+    let fit = least_trimmed_squares(&tied, &[1., 2., 3., 4.], 3, 10, &mut rng);
+    assert!(fit.gradient.is_nan() && fit.intercept.is_nan());
+    let fit = ransac(&tied, &[1., 2., 3., 4.], 0.5, 10, &mut rng);
+    assert!(fit.gradient.is_nan() && fit.intercept.is_nan());
+}
+
+#[test]
+pub fn method_comparison() {
+    use crate::{bland_altman, deming_regression, orthogonal_regression, passing_bablok};
+    let round = |x: f64| (x * 1e6).round();
+    let x = [4.1, 5.3, 6.0, 7.2, 8.8, 9.1, 10.4, 12.0, 13.5, 15.2];
+    let y = [4.5, 5.0, 6.6, 7.9, 8.5, 10.2, 11.1, 12.4, 14.6, 15.9];
+
+    let deming = deming_regression(&x, &y, 2.);
+    assert_eq!((round(deming.intercept), round(deming.gradient)), (-66133., 1062897.));
+    let orthogonal = orthogonal_regression(&x, &y);
+    assert_eq!((round(orthogonal.intercept), round(orthogonal.gradient)), (-87686., 1065250.));
+    assert_eq!(round(deming_regression(&x, &y, 1.).gradient), round(orthogonal.gradient));
+    let uncorrelated = deming_regression(&[1., 2., 3., 4., 5.], &[1., 3., 7., 3., 1.], 1.);
+    assert!(uncorrelated.gradient.is_nan() && uncorrelated.intercept.is_nan());
+    let passing = passing_bablok(&x, &y);
+    assert_eq!((round(passing.intercept), round(passing.gradient)), (66667., 1066667.));
+    // most slopes are below -1, so the shifted median is past the last slope
+    let falling = passing_bablok(&[1., 2., 3., 4., 5.], &[10., 8., 5., 3., 1.]);
+    assert!(falling.gradient.is_nan() && falling.intercept.is_nan());
+
+    let agreement = bland_altman(&x, &y, 0.95);
+    assert_eq!(round(agreement.bias.value), -510000.);
+    assert_eq!(round(agreement.standard_deviation), 488649.);
+    assert_eq!(round(agreement.limits_of_agreement.0), -1467734.);
+    assert_eq!(round(agreement.limits_of_agreement.1), 447734.);
+    assert_eq!((round(agreement.bias.lower), round(agreement.bias.upper)), (-859558., -160442.));
+}
+
+#[test]
+pub fn regularized_regression() {
+    use crate::{cross_validate_regularization, regularization_path, regularized_regression, Penalty};
+    let round = |x: f64| (x * 1e6).round();
+    let x1 = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 11., 12.];
+    let x2 = [2.1, 1.9, 3.5, 4.2, 4.8, 6.5, 6.9, 8.1, 8.8, 10.2, 10.9, 12.5];
+    let x3 = [5., 3., 6., 2., 7., 1., 8., 4., 9., 3., 6., 2.];
+    let y = [3.2, 4.1, 6.8, 7.5, 9.9, 11.2, 13.8, 14.1, 16.9, 18.2, 20.5, 21.9];
+    let predictors = [&x1[..], &x2, &x3];
+    let coefficients = |fit: &crate::RegularizedRegression| {
+        fit.coefficients.iter().map(|b| round(*b)).collect::<Vec<f64>>()
+    };
+
+    let ridge = regularized_regression(&predictors, &y, Penalty::Ridge, 0.5);
+    assert_eq!(round(ridge.intercept), 2503417.);
+    assert_eq!(coefficients(&ridge), vec![696613., 710245., 118204.]);
+    let lasso = regularized_regression(&predictors, &y, Penalty::Lasso, 0.3);
+    assert_eq!(round(lasso.intercept), 1569108.);
+    assert_eq!(coefficients(&lasso), vec![1535045., 113866., 6829.]);
+    let elastic = regularized_regression(&predictors, &y, Penalty::ElasticNet { alpha: 0.5 }, 0.3);
+    assert_eq!(coefficients(&elastic), vec![800849., 794189., 99140.]);
+    assert_eq!(round(elastic.predict(&[1., 2.1, 5.]) + elastic.residuals[0]), 3.2e6);
+    let constant = [2.; 12];
+    let with_constant = regularized_regression(&[&x1, &constant], &y, Penalty::Ridge, 0.5);
+    assert_eq!(with_constant.coefficients[1], 0.);
+    assert!(with_constant.intercept.is_finite() && with_constant.coefficients[0].is_finite());
+
+    let path = regularization_path(&predictors, &y, Penalty::Lasso, 20);
+    assert_eq!(round(path.lambdas[0]), 5978327.);
+    assert_eq!(path.coefficients[0], vec![0., 0., 0.]);
+    assert!(path.model(19, &predictors, &y).nonzero() > 0);
+    let csv = path.to_csv();
+    assert!(csv.starts_with("lambda,intercept,b1,b2,b3\n"));
+    assert_eq!(csv.lines().count(), 21);
+
+    let cv = cross_validate_regularization(&predictors, &y, Penalty::Lasso, 20, 4);
+    assert!(cv.lambda_1se >= cv.lambda_min);
+    let best = cv.lambdas.iter().position(|l| *l == cv.lambda_min).unwrap();
+    assert!(cv.mean_errors.iter().all(|e| *e >= cv.mean_errors[best]));
+    let loo = cross_validate_regularization(&predictors, &y, Penalty::Lasso, 20, y.len());
+    assert_eq!(loo.mean_errors.len(), 20);
+}
+
+#[test]
+#[should_panic(expected = "cross validation needs between 2 folds")]
+pub fn regularization_with_one_fold() {
+    let x = [1., 2., 3., 4., 5.];
+    crate::cross_validate_regularization(&[&x], &[2., 4., 5., 4., 6.], crate::Penalty::Ridge, 5, 1);
+}
+
+#[test]
+#[should_panic(expected = "cross validation needs between 2 folds")]
+pub fn regularization_with_more_folds_than_observations() {
+    let x = [1., 2., 3., 4., 5.];
+    crate::cross_validate_regularization(&[&x], &[2., 4., 5., 4., 6.], crate::Penalty::Ridge, 5, 6);
+}
+
+#[test]
+pub fn generalized_linear_models() {
+    use crate::{glm, logistic_regression, Family, Link};
+    let round = |x: f64| (x * 1e6).round();
+    let x = [0.5, 1.2, 1.9, 2.4, 3.1, 3.6, 4.2, 4.9, 5.5, 6.1, 6.8, 7.4];
+    let churned = [0., 0., 1., 0., 0., 1., 0., 1., 1., 1., 0., 1.];
+
+    let logit = logistic_regression(&[&x], &churned);
+    assert!(logit.converged);
+    assert_eq!((round(logit.coefficients[0]), round(logit.coefficients[1])), (-1846950., 465848.));
+    let wald = logit.wald_tests(0.95);
+    assert_eq!((round(wald[0].standard_error), round(wald[1].standard_error)), (1455792., 329922.));
+    assert_eq!(round(logit.deviance), 14200657.);
+    assert_eq!(round(logit.null_deviance), 16635532.);
+    assert_eq!(round(logit.odds_ratios(0.95)[1].value), round(0.465847818048983f64.exp()));
+    assert_eq!(round(logit.predict(&[1.2])), round(logit.fitted_values[1]));
+    assert_eq!(logit.deviance_test().df, Some(1.));
+    assert!(!logit.separation);
+    let separated = logistic_regression(&[&x[..6]], &[0., 0., 0., 1., 1., 1.]);
+    assert!(separated.separation);
+
+    // proportions of successes weighted by their number of trials
+    let dose = [1., 2., 3., 4., 5., 6.];
+    let trials = [10., 12., 11., 10., 9., 12.];
+    let successes = [1., 2., 5., 6., 7., 11.];
+    let proportions = successes
+        .iter()
+        .zip(trials.iter())
+        .map(|(k, t)| k / t)
+        .collect::<Vec<f64>>();
+    let grouped = glm(&[&dose], &proportions, Family::Binomial, Link::Logit, None, Some(&trials), true);
+    assert_eq!((round(grouped.coefficients[0]), round(grouped.coefficients[1])), (-3186871., 915865.));
+    assert_eq!(round(grouped.deviance), 346141.);
+    assert_eq!(round(grouped.wald_tests(0.95)[1].standard_error), 221876.);
+    let (mut binary_dose, mut binary) = (vec![], vec![]);
+    for i in 0..6 {
+        for j in 0..trials[i] as usize {
+            binary_dose.push(dose[i]);
+            binary.push(if (j as f64) < successes[i] { 1. } else { 0. });
+        }
+    }
+    let ungrouped = logistic_regression(&[&binary_dose], &binary);
+    assert_eq!(round(ungrouped.coefficients[1]), round(grouped.coefficients[1]));
+
+    let cloglog = glm(&[&dose], &proportions, Family::Binomial, Link::CLogLog, None, Some(&trials), true);
+    assert_eq!((round(cloglog.coefficients[0]), round(cloglog.coefficients[1])), (-2568893., 595646.));
+    assert_eq!(round(cloglog.wald_tests(0.95)[1].standard_error), 133630.);
+    assert_eq!(round(cloglog.deviance), 784415.);
+
+    let probit = glm(&[&x], &churned, Family::Binomial, Link::Probit, None, None, true);
+    assert_eq!((round(probit.coefficients[0]), round(probit.coefficients[1])), (-1134152., 283647.));
+
+    let counts = [2., 3., 6., 7., 8., 9., 10., 12., 15., 18., 20., 25.];
+    let exposure = [1., 1., 2., 2., 2., 2., 2., 2., 3., 3., 3., 3.]
+        .iter()
+        .map(|e: &f64| e.ln())
+        .collect::<Vec<f64>>();
+    let poisson = glm(&[&x], &counts, Family::Poisson, Link::Log, Some(&exposure), None, true);
+    assert_eq!((round(poisson.coefficients[0]), round(poisson.coefficients[1])), (845333., 163303.));
+    assert_eq!(round(poisson.wald_tests(0.95)[1].standard_error), 45911.);
+    assert_eq!(round(poisson.deviance), 1013120.);
+    let offset_only = glm(&[], &counts, Family::Poisson, Link::Log, Some(&exposure), None, false);
+    assert!(offset_only.coefficients.is_empty());
+    let fitted = offset_only.fitted_values.iter().map(|mu| round(*mu)).collect::<Vec<f64>>();
+    assert_eq!(fitted[..3], [1e6, 1e6, 2e6]);
+    assert_eq!(fitted[11], 3e6);
+
+    let y = [1.2, 1.9, 2.1, 3.5, 3.2, 4.8, 5.1, 6.9, 6.2, 8.8, 9.5, 12.1];
+    let gamma = glm(&[&x], &y, Family::Gamma, Link::Log, None, None, true);
+    assert_eq!((round(gamma.coefficients[0]), round(gamma.coefficients[1])), (273050., 308752.));
+    assert_eq!((round(gamma.deviance), round(gamma.dispersion)), (235949., 24154.));
+    assert_eq!(round(gamma.wald_tests(0.95)[1].standard_error), 20970.);
+
+    let gaussian = glm(&[&x], &y, Family::Gaussian, Link::Identity, None, None, true);
+    let ols = crate::multiple_regression(&[&x], &y, true).summary(&[&x], 0.95);
+    assert_eq!(round(gaussian.coefficients[1]), round(ols.coefficients[1].estimate));
+    assert_eq!(
+        round(gaussian.wald_tests(0.95)[1].standard_error),
+        round(ols.coefficients[1].standard_error)
+    );
+}
@@ -5,7 +5,7 @@ pub mod moving_average {
 
     pub fn simple(input: &Input, n: usize) -> Vec<Num> {
         let n_scale = n as Num;
-        user_original(input, n, |slice| sum(&slice) / n_scale)
+        user_original(input, n, |slice| sum(slice) / n_scale)
     }
 
     pub fn weighted(input: &Input, n: usize) -> Vec<Num> {
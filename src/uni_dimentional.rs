@@ -1,6 +1,10 @@
 /// functions that takes only one argument will be found here
 use std::collections::{BTreeMap, HashMap, HashSet};
 use crate::generic_types::{Input, OrdFloat, Num};
+use crate::distribution::Normal;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
 /// sorts floating point numbers without calling `partial_cmp`  
 /// algorithm will first compare the integer part of the number then compare the fractional part if the integer part is equal 
@@ -29,22 +33,106 @@ pub fn sum(input: &Input) -> Num {
 /// Input must be sorted or it will yeild a wrong result
 pub fn median(input: &Input) -> Num {
     let half = input.len() / 2;
-    let mut iter = input.iter();
-    let n = if let Some(e) = iter.nth(half) {
-        *e
+    if input.len() % 2 == 1 {
+        input[half]
     } else {
-        unreachable!("")
+        (input[half - 1] + input[half]) / 2 as Num
+    }
+}
+
+/// how `rank` orders tied values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieMethod {
+    /// the average of the ranks the tied values occupy
+    Average,
+    /// the lowest of the ranks the tied values occupy
+    Min,
+    /// the highest of the ranks the tied values occupy
+    Max,
+    /// like `Min`, but the next distinct value gets the next rank so there are no gaps
+    Dense,
+    /// tied values are ranked in the order they appear
+    Ordinal,
+    /// tied values are ranked in a random order, reproducible from the seed
+    Random { seed: u64 },
+}
+
+/// normal scores computed from ranks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalScores {
+    /// `quantile(rank / (n + 1))`
+    VanDerWaerden,
+    /// `quantile((rank - 3/8) / (n + 1/4))`
+    Blom,
+}
+
+/// indices that sort the input in ascending order. the sort is stable
+pub fn argsort(input: &Input) -> Vec<usize> {
+    let mut order = (0..input.len()).collect::<Vec<usize>>();
+    order.sort_by_key(|i| OrdFloat(input[*i]));
+    order
+}
+
+/// ranks the input from 1 to `input.len()`, with ties handled according to `ties`
+pub fn rank(input: &Input, ties: TieMethod) -> Vec<Num> {
+    let mut order = argsort(input);
+    let mut rng = match ties {
+        TieMethod::Random { seed } => Some(StdRng::seed_from_u64(seed)),
+        _ => None,
     };
 
-    if half % 2 == 1 {
-        if let Some(e) = iter.next() {
-            (n + *e) / 2 as Num
-        } else {
-            n
+    let mut ranks = vec![0 as Num; input.len()];
+    let mut start = 0;
+    let mut distinct = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && input[order[end]] == input[order[start]] {
+            end += 1;
         }
-    } else {
-        n
+        distinct += 1;
+        if let Some(rng) = rng.as_mut() {
+            order[start..end].shuffle(rng);
+        }
+        // ranks are 1 based, so the tie block covers start + 1 ..= end
+        for (k, i) in order[start..end].iter().enumerate() {
+            ranks[*i] = match ties {
+                TieMethod::Average => (start + 1 + end) as Num / 2 as Num,
+                TieMethod::Min => (start + 1) as Num,
+                TieMethod::Max => end as Num,
+                TieMethod::Dense => distinct as Num,
+                TieMethod::Ordinal | TieMethod::Random { .. } => (start + 1 + k) as Num,
+            };
+        }
+        start = end;
     }
+    ranks
+}
+
+/// percentile rank of `value`, the percentage of the input below it counting ties as half
+pub fn percentile_rank(input: &Input, value: Num) -> Num {
+    let (below, equal) = input.iter().fold((0 as Num, 0 as Num), |acc, x| {
+        if *x < value {
+            (acc.0 + 1., acc.1)
+        } else if *x == value {
+            (acc.0, acc.1 + 1.)
+        } else {
+            acc
+        }
+    });
+    100 as Num * (below + equal / 2 as Num) / input.len() as Num
+}
+
+/// normal scores of the input, the standard normal quantiles of its average ranks
+pub fn normal_scores(input: &Input, method: NormalScores) -> Vec<Num> {
+    let n = input.len() as Num;
+    let normal = Normal::standard();
+    rank(input, TieMethod::Average)
+        .iter()
+        .map(|r| match method {
+            NormalScores::VanDerWaerden => normal.quantile(r / (n + 1.)),
+            NormalScores::Blom => normal.quantile((r - 0.375) / (n + 0.25)),
+        })
+        .collect()
 }
 
 /// find the value that is most frequently seen in the input
@@ -57,7 +145,7 @@ pub fn mode(input: &Input) -> (usize, HashSet<OrdFloat>) {
     let mut map = HashMap::new();
     input.iter().for_each(|i| {
         let i = OrdFloat(*i);
-        map.entry(i).or_insert(0 as usize);
+        map.entry(i).or_insert(0_usize);
 
         let count = map.get_mut(&i).unwrap();
         *count += 1;
@@ -133,12 +221,11 @@ pub fn geometric_mean(input: &Input) -> Num {
     let num_one = 1 as Num;
     let num_zero = 0 as Num;
 
-    if input.len() > 0 {
+    if input.is_empty() {
         return 0.;
     }
 
-    let mut iter = input.iter();
-    let mut acc = *iter.next().unwrap();
+    let mut acc = num_one;
     for n in input {
         if *n == num_zero {
             acc = 0.;
@@ -172,7 +259,7 @@ pub fn sample_variance(input: &Input) -> Num {
         .iter()
         .fold(0 as Num, |acc, n| acc + ((n - avg).powi(2)));
 
-    (var / ((input.len() - 1) as Num)).sqrt()
+    var / ((input.len() - 1) as Num)
 }
 
 pub fn cumulative_sum(input: &Input) -> Vec<Num> {
@@ -195,28 +282,12 @@ pub fn standard_deviation_sample(input: &Input) -> Num {
     sample_variance(input).powf(0.5)
 }
 
+/// median of the absolute deviations from the median.
+/// Input must be sorted or it will yeild a wrong result
 pub fn median_absolute_deviaiton(input: &Input) -> Num {
     let m = median(input);
-
-    let half = input.len() / 2;
-    let median = if half % 2 == 1 {
-        let mut skipper = input.iter().skip(half);
-        let mut n = if let Some(e) = skipper.next() {
-            (*e - m).abs()
-        } else {
-            unreachable!("")
-        };
-        if let Some(e) = skipper.next() {
-            n += (*e - m).abs()
-        };
-
-        n / 2 as Num
-    } else {
-        let n = *input.iter().skip(half).next().unwrap();
-        (n - m).abs()
-    };
-    // abs median
-    median
+    let deviations = input.iter().map(|n| (n - m).abs()).collect::<Vec<Num>>();
+    median(&sort_float(&deviations))
 }
 
 pub mod percentile {
@@ -258,12 +329,12 @@ pub fn entropy(input: &Input) -> Num {
             vec.push(*n);
             acc + n
         });
-        vec.iter_mut().for_each(|i| *i = *i / sum);
+        vec.iter_mut().for_each(|i| *i /= sum);
         vec
     };
 
     let r = normalized.iter().fold(0 as Num, |result, n| {
-        if result == 0 as Num {
+        if *n == 0 as Num {
             result
         } else {
             let e = std::f64::consts::E;